@@ -0,0 +1,53 @@
+//! Provides the runtime specific functions for a generic Cortex-M platform
+
+use cortex_m::peripheral::{scb::VectActive, SCB};
+
+#[cfg(feature = "multicore")]
+extern "Rust" {
+    /// Gets the index of the current core; must be provided by the user if the `multicore` feature is enabled
+    fn get_core_id() -> usize;
+}
+
+/// Ensures that `code` is run exclusively, without being subject to multicore/-thread race conditions or interrupts
+#[no_mangle]
+#[doc(hidden)]
+#[allow(non_snake_case)]
+pub fn _runtime_threadsafe_e0LtH0x3(code: &mut dyn FnMut()) {
+    critical_section::with(|_| code())
+}
+
+/// Ensures that `code` is run exclusively, without being subject to interrupts
+///
+/// # Note
+/// This is the same critical section as `_runtime_threadsafe_e0LtH0x3`, as the critical section implementation is the
+/// only generic way to disable interrupts without interfering with a HAL or RTOS.
+#[no_mangle]
+#[doc(hidden)]
+#[allow(non_snake_case)]
+pub fn _runtime_interruptsafe_1l52Ge5e(code: &mut dyn FnMut()) {
+    critical_section::with(|_| code())
+}
+
+/// Gets the __unique__ and __persistent__ identifier of the current thread (e.g. a session-unique thread IDs or the
+/// index of the current CPU core on bare-metal systems).
+///
+/// # Note
+/// If the `multicore` feature is enabled, this is the index of the current core as returned by the user-provided
+/// `get_core_id`; otherwise there is only one thread with the ID `0`.
+#[no_mangle]
+#[doc(hidden)]
+#[allow(non_snake_case)]
+pub fn _runtime_threadid_ZhZIZBv4() -> usize {
+    #[cfg(feature = "multicore")]
+    return unsafe { get_core_id() };
+    #[cfg(not(feature = "multicore"))]
+    return 0;
+}
+
+/// Tests whether we are currently in an interrupt context or not
+#[no_mangle]
+#[doc(hidden)]
+#[allow(non_snake_case)]
+pub fn _runtime_isinterrupted_v5tnnoC7() -> bool {
+    SCB::vect_active() != VectActive::ThreadMode
+}
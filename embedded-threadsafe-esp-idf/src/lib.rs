@@ -0,0 +1,8 @@
+#![doc = include_str!("../README.md")]
+
+#[cfg(target_os = "espidf")]
+#[doc(hidden)]
+pub mod runtime;
+
+// Re-export everything
+pub use embedded_threadsafe::*;
@@ -0,0 +1,101 @@
+//! Provides the runtime specific functions for the ESP-IDF `std` environment
+
+use core::{cell::UnsafeCell, ffi::c_void};
+
+/// A FreeRTOS `BaseType_t`
+#[allow(non_camel_case_types)]
+type BaseType_t = i32;
+
+/// A FreeRTOS spinlock (`portMUX_TYPE` aka `spinlock_t`)
+#[repr(C)]
+#[allow(non_camel_case_types)]
+struct portMUX_TYPE {
+    /// The ID of the owning core or `SPINLOCK_FREE`
+    owner: u32,
+    /// The recursion count of the owning core
+    count: u32,
+}
+
+/// The `owner` value of an unlocked spinlock
+const SPINLOCK_FREE: u32 = 0xB33F_FFFF;
+/// The timeout to wait forever for a spinlock
+const PORTMUX_NO_TIMEOUT: BaseType_t = -1;
+
+extern "C" {
+    /// Disables interrupts on the current core and acquires the spinlock; this function is ISR-safe and recursive
+    fn xPortEnterCriticalTimeout(mux: *mut portMUX_TYPE, timeout: BaseType_t) -> BaseType_t;
+    /// Releases the spinlock and restores interrupts on the current core if the recursion count drops to zero
+    fn vPortExitCritical(mux: *mut portMUX_TYPE);
+    /// Tests whether the current core is in an ISR context
+    fn xPortInIsrContext() -> BaseType_t;
+    /// Gets the handle of the task that is currently running on this core
+    fn xTaskGetCurrentTaskHandle() -> *mut c_void;
+}
+
+/// The global spinlock that serializes all critical sections across both cores
+struct GlobalMux(UnsafeCell<portMUX_TYPE>);
+unsafe impl Sync for GlobalMux {
+    // Marker trait, no members to implement
+}
+
+/// The global spinlock
+static MUX: GlobalMux = GlobalMux(UnsafeCell::new(portMUX_TYPE { owner: SPINLOCK_FREE, count: 0 }));
+
+/// Leaves the critical section on drop
+struct CriticalSectionGuard;
+impl Drop for CriticalSectionGuard {
+    fn drop(&mut self) {
+        unsafe { vPortExitCritical(MUX.0.get()) };
+    }
+}
+
+/// Ensures that `code` is run exclusively, without being subject to multicore/-thread race conditions or interrupts
+///
+/// # Note
+/// This is a FreeRTOS critical section on a global spinlock, so it disables interrupts on the current core and is safe
+/// to use from ISRs. The spinlock counts recursive acquisitions by the owning core, so critical sections can be nested.
+/// Blocking FreeRTOS APIs must not be called from within `code`.
+#[no_mangle]
+#[doc(hidden)]
+#[allow(non_snake_case)]
+pub fn _runtime_threadsafe_e0LtH0x3(code: &mut dyn FnMut()) {
+    // Enter the critical section; waiting forever cannot fail
+    unsafe { xPortEnterCriticalTimeout(MUX.0.get(), PORTMUX_NO_TIMEOUT) };
+    let _guard = CriticalSectionGuard;
+
+    // Run the code
+    code()
+}
+
+/// Ensures that `code` is run exclusively, without being subject to interrupts
+///
+/// # Note
+/// Interrupts can only be masked together with acquiring a spinlock on ESP-IDF, so this is the same critical section as
+/// `_runtime_threadsafe_e0LtH0x3`.
+#[no_mangle]
+#[doc(hidden)]
+#[allow(non_snake_case)]
+pub fn _runtime_interruptsafe_1l52Ge5e(code: &mut dyn FnMut()) {
+    _runtime_threadsafe_e0LtH0x3(code)
+}
+
+/// Gets the __unique__ and __persistent__ identifier of the current thread (e.g. a session-unique thread IDs or the
+/// index of the current CPU core on bare-metal systems).
+///
+/// # Note
+/// The thread ID is the handle of the current FreeRTOS task; within an ISR, this is the handle of the interrupted task.
+/// A handle may only be reused after its task has been deleted, so no two living tasks share a thread ID.
+#[no_mangle]
+#[doc(hidden)]
+#[allow(non_snake_case)]
+pub fn _runtime_threadid_ZhZIZBv4() -> usize {
+    unsafe { xTaskGetCurrentTaskHandle() as usize }
+}
+
+/// Tests whether we are currently in an interrupt context or not
+#[no_mangle]
+#[doc(hidden)]
+#[allow(non_snake_case)]
+pub fn _runtime_isinterrupted_v5tnnoC7() -> bool {
+    unsafe { xPortInIsrContext() != 0 }
+}
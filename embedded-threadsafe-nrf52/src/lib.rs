@@ -0,0 +1,11 @@
+#![no_std]
+#![doc = include_str!("../README.md")]
+
+#[doc(hidden)]
+pub mod runtime;
+
+/// The amount of cores of the nRF52, i.e. the upper bound (exclusive) for thread IDs
+pub const MAX_CORES: usize = 1;
+
+// Re-export everything
+pub use embedded_threadsafe::*;
@@ -0,0 +1,43 @@
+//! Provides the runtime specific functions for an nRF52 platform
+
+use cortex_m::peripheral::{scb::VectActive, SCB};
+
+/// Ensures that `code` is run exclusively, without being subject to multicore/-thread race conditions or interrupts
+#[no_mangle]
+#[doc(hidden)]
+#[allow(non_snake_case)]
+pub fn _runtime_threadsafe_e0LtH0x3(code: &mut dyn FnMut()) {
+    critical_section::with(|_| code())
+}
+
+/// Ensures that `code` is run exclusively, without being subject to interrupts
+///
+/// # Note
+/// The nRF52 is single-core, so this is the same critical section as `_runtime_threadsafe_e0LtH0x3`. This also ensures
+/// that we don't mask interrupts which are reserved by the SoftDevice.
+#[no_mangle]
+#[doc(hidden)]
+#[allow(non_snake_case)]
+pub fn _runtime_interruptsafe_1l52Ge5e(code: &mut dyn FnMut()) {
+    critical_section::with(|_| code())
+}
+
+/// Gets the __unique__ and __persistent__ identifier of the current thread (e.g. a session-unique thread IDs or the
+/// index of the current CPU core on bare-metal systems).
+///
+/// # Note
+/// The nRF52 is single-core, so there is only one thread with the ID `0`.
+#[no_mangle]
+#[doc(hidden)]
+#[allow(non_snake_case)]
+pub fn _runtime_threadid_ZhZIZBv4() -> usize {
+    0
+}
+
+/// Tests whether we are currently in an interrupt context or not
+#[no_mangle]
+#[doc(hidden)]
+#[allow(non_snake_case)]
+pub fn _runtime_isinterrupted_v5tnnoC7() -> bool {
+    SCB::vect_active() != VectActive::ThreadMode
+}
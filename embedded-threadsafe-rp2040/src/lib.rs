@@ -4,5 +4,8 @@
 #[doc(hidden)]
 pub mod runtime;
 
+/// The amount of cores of the RP2040, i.e. the upper bound (exclusive) for thread IDs
+pub const MAX_CORES: usize = 2;
+
 // Re-export everything
 pub use embedded_threadsafe::*;
@@ -0,0 +1,86 @@
+//! Provides the runtime specific functions for `std` environments
+
+use std::{
+    cell::Cell,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Mutex, PoisonError,
+    },
+};
+
+/// The global lock that serializes all critical sections
+static LOCK: Mutex<()> = Mutex::new(());
+/// The next thread ID to assign
+static NEXT_THREAD_ID: AtomicUsize = AtomicUsize::new(0);
+
+thread_local! {
+    /// The critical section nesting depth of the current thread
+    static DEPTH: Cell<usize> = const { Cell::new(0) };
+    /// The ID of the current thread
+    static THREAD_ID: usize = NEXT_THREAD_ID.fetch_add(1, Ordering::Relaxed);
+}
+
+/// Restores the critical section nesting depth of the current thread on drop
+struct DepthGuard {
+    /// The depth to restore
+    depth: usize,
+}
+impl Drop for DepthGuard {
+    fn drop(&mut self) {
+        DEPTH.with(|depth| depth.set(self.depth));
+    }
+}
+
+/// Ensures that `code` is run exclusively, without being subject to multicore/-thread race conditions or interrupts
+///
+/// # Note
+/// The global lock is only acquired by the outermost critical section of a thread, so critical sections can be nested.
+#[no_mangle]
+#[doc(hidden)]
+#[allow(non_snake_case)]
+pub fn _runtime_threadsafe_e0LtH0x3(code: &mut dyn FnMut()) {
+    // Acquire the lock if we are not already inside a critical section
+    let depth = DEPTH.with(|depth| depth.replace(depth.get() + 1));
+    let _depth_guard = DepthGuard { depth };
+    let _lock = match depth {
+        0 => Some(LOCK.lock().unwrap_or_else(PoisonError::into_inner)),
+        _ => None,
+    };
+
+    // Run the code
+    code()
+}
+
+/// Ensures that `code` is run exclusively, without being subject to interrupts
+///
+/// # Note
+/// There are no ISRs in a `std` environment, so this is the same critical section as `_runtime_threadsafe_e0LtH0x3`.
+#[no_mangle]
+#[doc(hidden)]
+#[allow(non_snake_case)]
+pub fn _runtime_interruptsafe_1l52Ge5e(code: &mut dyn FnMut()) {
+    _runtime_threadsafe_e0LtH0x3(code)
+}
+
+/// Gets the __unique__ and __persistent__ identifier of the current thread (e.g. a session-unique thread IDs or the
+/// index of the current CPU core on bare-metal systems).
+///
+/// # Note
+/// Thread IDs are assigned sequentially on first use and are never reused.
+#[no_mangle]
+#[doc(hidden)]
+#[allow(non_snake_case)]
+pub fn _runtime_threadid_ZhZIZBv4() -> usize {
+    THREAD_ID.with(|thread_id| *thread_id)
+}
+
+/// Tests whether we are currently in an interrupt context or not
+///
+/// # Note
+/// There are no ISRs in a `std` environment, so this is always `false`.
+#[no_mangle]
+#[doc(hidden)]
+#[allow(non_snake_case)]
+pub fn _runtime_isinterrupted_v5tnnoC7() -> bool {
+    false
+}
@@ -0,0 +1,57 @@
+use embedded_threadsafe_std::{AccessError, LocalCell, SharedCell};
+use std::thread;
+
+/// The amount of threads to spawn
+const THREADS: usize = 8;
+/// The amount of increments per thread
+const INCREMENTS: usize = 1000;
+
+#[test]
+fn shared_cell_concurrent_increments() {
+    // Increment a global counter from multiple threads
+    static COUNTER: SharedCell<usize> = SharedCell::new(0);
+    let threads: Vec<_> = (0..THREADS)
+        .map(|_| {
+            thread::spawn(|| {
+                for _ in 0..INCREMENTS {
+                    COUNTER.scope(|counter| *counter += 1);
+                }
+            })
+        })
+        .collect();
+    for thread in threads {
+        thread.join().expect("increment thread panicked");
+    }
+
+    // Ensure that no increment got lost
+    let counter = COUNTER.scope(|counter| *counter);
+    assert_eq!(counter, THREADS * INCREMENTS);
+}
+
+#[test]
+fn local_cell_foreign_thread() {
+    // Create a cell that is bound to the current thread and access it from other threads
+    let cell = LocalCell::new(7usize);
+    thread::scope(|scope| {
+        for _ in 0..THREADS {
+            let thread = scope.spawn(|| cell.try_scope(|value| *value));
+            let result = thread.join().expect("access thread panicked");
+            assert!(matches!(result, Err(AccessError::WrongThread { .. })), "unexpected result: {result:?}");
+        }
+    });
+
+    // Ensure that the cell is still accessible from the owning thread
+    assert_eq!(cell.try_scope(|value| *value), Ok(7));
+}
+
+#[test]
+fn nested_scope_is_reentrant() {
+    // Access both cell types from within their own scopes
+    let shared = SharedCell::new(0usize);
+    let result = shared.scope(|_| shared.try_scope(|_| ()));
+    assert_eq!(result, Err(AccessError::Reentrant));
+
+    let local = LocalCell::new(0usize);
+    let result = local.scope(|_| local.try_scope(|_| ()));
+    assert_eq!(result, Err(AccessError::Reentrant));
+}
@@ -0,0 +1,27 @@
+use embedded_threadsafe::runtime::{self, RuntimeValidator};
+// Link the `std` runtime which provides the runtime functions under test
+use embedded_threadsafe_std as _;
+use std::{sync::mpsc, thread, time::Duration};
+
+/// The time after which a hanging validation is considered a deadlock
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Validates the `std` runtime
+struct StdRuntime;
+unsafe impl RuntimeValidator for StdRuntime {
+    // Use the provided validation methods
+}
+
+#[test]
+fn std_runtime_validation() {
+    // Run the validation in a separate thread, as a non-re-entrant runtime would hang instead of failing
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || {
+        let valid = runtime::run_runtime_validation(&StdRuntime);
+        let _ = sender.send(valid);
+    });
+
+    // Ensure that the validation completes and succeeds
+    let valid = receiver.recv_timeout(TIMEOUT).expect("runtime validation deadlocked");
+    assert!(valid, "runtime validation failed");
+}
@@ -0,0 +1,116 @@
+//! A lock-free cell for primitive integer types
+
+use core::{
+    fmt::{self, Debug, Formatter},
+    sync::atomic::{self, Ordering},
+};
+
+/// A primitive integer type that has a corresponding atomic type
+pub trait Atomic: Copy + sealed::Sealed {
+    /// The corresponding atomic type
+    type Atomic;
+}
+
+/// A lock-free cell for primitive integer types
+///
+/// # Note
+/// Unlike the other cells, this cell does not use critical sections but the atomic instructions of the target. It is
+/// only available for types where the target supports atomic compare-and-swap operations.
+pub struct AtomicCell<T>
+where
+    T: Atomic,
+{
+    /// The wrapped value
+    inner: T::Atomic,
+}
+
+/// A lock-free cell for `u32`, which is the native atomic width of most 32 bit microcontrollers
+#[cfg(target_has_atomic = "32")]
+pub type AtomicU32Cell = AtomicCell<u32>;
+
+/// Implements the atomic cell for the given primitive type
+macro_rules! impl_atomic_cell {
+    ($type:ty, $atomic:ty, $width:literal) => {
+        #[cfg(target_has_atomic = $width)]
+        impl sealed::Sealed for $type {
+            // Marker trait, no members to implement
+        }
+        #[cfg(target_has_atomic = $width)]
+        impl Atomic for $type {
+            type Atomic = $atomic;
+        }
+        #[cfg(target_has_atomic = $width)]
+        impl AtomicCell<$type> {
+            /// Creates a new cell
+            pub const fn new(value: $type) -> Self {
+                Self { inner: <$atomic>::new(value) }
+            }
+
+            /// Provides scoped access to a copy of the underlying value and atomically stores the modified copy back
+            ///
+            /// # Note
+            /// If the value is modified concurrently while the scope is running, the modified copy is discarded and
+            /// the scope is called again with the new value; the scope should thus be free of side effects.
+            pub fn scope<F, FR>(&self, mut scope: F) -> FR
+            where
+                F: FnMut(&mut $type) -> FR,
+            {
+                let mut current = self.inner.load(Ordering::SeqCst);
+                loop {
+                    // Call the scope with a copy of the current value
+                    let mut value = current;
+                    let result = scope(&mut value);
+
+                    // Store the modified value back if the underlying value has not been modified in the meantime
+                    match self.inner.compare_exchange_weak(current, value, Ordering::SeqCst, Ordering::SeqCst) {
+                        Ok(_) => return result,
+                        Err(actual) => current = actual,
+                    }
+                }
+            }
+
+            /// Provides scoped read-only access to a copy of the underlying value
+            pub fn scope_ref<F, FR>(&self, scope: F) -> FR
+            where
+                F: FnOnce(&$type) -> FR,
+            {
+                let value = self.load();
+                scope(&value)
+            }
+
+            /// Loads the underlying value
+            pub fn load(&self) -> $type {
+                self.inner.load(Ordering::SeqCst)
+            }
+
+            /// Stores a new value
+            pub fn store(&self, value: $type) {
+                self.inner.store(value, Ordering::SeqCst)
+            }
+        }
+        #[cfg(target_has_atomic = $width)]
+        impl Debug for AtomicCell<$type> {
+            fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+                f.debug_tuple("AtomicCell").field(&self.load()).finish()
+            }
+        }
+    };
+}
+impl_atomic_cell!(u8, atomic::AtomicU8, "8");
+impl_atomic_cell!(u16, atomic::AtomicU16, "16");
+impl_atomic_cell!(u32, atomic::AtomicU32, "32");
+impl_atomic_cell!(u64, atomic::AtomicU64, "64");
+impl_atomic_cell!(usize, atomic::AtomicUsize, "ptr");
+impl_atomic_cell!(i8, atomic::AtomicI8, "8");
+impl_atomic_cell!(i16, atomic::AtomicI16, "16");
+impl_atomic_cell!(i32, atomic::AtomicI32, "32");
+impl_atomic_cell!(i64, atomic::AtomicI64, "64");
+impl_atomic_cell!(isize, atomic::AtomicIsize, "ptr");
+
+/// Seals the [`Atomic`] trait
+mod sealed {
+    /// A sealing marker trait
+    pub trait Sealed {
+        // Marker trait, no members to implement
+    }
+}
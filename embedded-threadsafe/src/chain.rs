@@ -0,0 +1,85 @@
+//! Atomic access to multiple shared cells within a single critical section
+
+use crate::{token::CriticalSectionToken, SharedCell};
+
+/// Provides scoped access to the underlying values of multiple [`SharedCell`]s within a single critical section
+///
+/// This is a shorthand for [`scope2`], [`scope3`] and [`scope4`] depending on the amount of cells.
+///
+/// # Example
+/// ```rust
+/// use embedded_threadsafe::{scope_chain, SharedCell};
+///
+/// // A packet counter and a byte counter that must be updated together
+/// static PACKETS: SharedCell<u32> = SharedCell::new(0);
+/// static BYTES: SharedCell<u64> = SharedCell::new(0);
+///
+/// fn on_packet(len: usize) {
+///     scope_chain!(&PACKETS, &BYTES, |packets: &mut u32, bytes: &mut u64| {
+///         *packets += 1;
+///         *bytes += len as u64;
+///     });
+/// }
+/// ```
+#[macro_export]
+macro_rules! scope_chain {
+    ($a:expr, $b:expr, $c:expr, $d:expr, $scope:expr $(,)?) => {
+        $crate::chain::scope4($a, $b, $c, $d, $scope)
+    };
+    ($a:expr, $b:expr, $c:expr, $scope:expr $(,)?) => {
+        $crate::chain::scope3($a, $b, $c, $scope)
+    };
+    ($a:expr, $b:expr, $scope:expr $(,)?) => {
+        $crate::chain::scope2($a, $b, $scope)
+    };
+}
+
+/// Provides scoped access to the underlying values of two cells within a single critical section
+///
+/// # Panic
+/// This function will panic if a cell is already locked (e.g. if the same cell is passed twice) or has been poisoned by
+/// a panicking scope
+pub fn scope2<A, B, F, FR>(a: &SharedCell<A>, b: &SharedCell<B>, scope: F) -> FR
+where
+    F: FnOnce(&mut A, &mut B) -> FR,
+{
+    CriticalSectionToken::with(|token| a.scope_with_token(token, |a| b.scope_with_token(token, |b| scope(a, b))))
+}
+
+/// Provides scoped access to the underlying values of three cells within a single critical section
+///
+/// # Panic
+/// This function will panic if a cell is already locked (e.g. if the same cell is passed twice) or has been poisoned by
+/// a panicking scope
+pub fn scope3<A, B, C, F, FR>(a: &SharedCell<A>, b: &SharedCell<B>, c: &SharedCell<C>, scope: F) -> FR
+where
+    F: FnOnce(&mut A, &mut B, &mut C) -> FR,
+{
+    CriticalSectionToken::with(|token| {
+        a.scope_with_token(token, |a| b.scope_with_token(token, |b| c.scope_with_token(token, |c| scope(a, b, c))))
+    })
+}
+
+/// Provides scoped access to the underlying values of four cells within a single critical section
+///
+/// # Panic
+/// This function will panic if a cell is already locked (e.g. if the same cell is passed twice) or has been poisoned by
+/// a panicking scope
+pub fn scope4<A, B, C, D, F, FR>(
+    a: &SharedCell<A>,
+    b: &SharedCell<B>,
+    c: &SharedCell<C>,
+    d: &SharedCell<D>,
+    scope: F,
+) -> FR
+where
+    F: FnOnce(&mut A, &mut B, &mut C, &mut D) -> FR,
+{
+    CriticalSectionToken::with(|token| {
+        a.scope_with_token(token, |a| {
+            b.scope_with_token(token, |b| {
+                c.scope_with_token(token, |c| d.scope_with_token(token, |d| scope(a, b, c, d)))
+            })
+        })
+    })
+}
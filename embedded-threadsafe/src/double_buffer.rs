@@ -0,0 +1,146 @@
+//! A double-buffered, thread-local cell to exchange data across interrupt contexts
+
+use crate::InterruptCell;
+use core::cell::UnsafeCell;
+
+/// The bookkeeping of a double-buffered cell
+struct BufferState {
+    /// The index of the active slot
+    active: usize,
+    /// The amount of readers per slot
+    readers: [usize; 2],
+    /// Whether a writer is currently filling the inactive slot
+    writing: bool,
+}
+
+/// Releases a read access to a buffer slot on drop
+struct ReadGuard<'a> {
+    /// The bookkeeping
+    state: &'a InterruptCell<BufferState>,
+    /// The index of the slot that is being read
+    index: usize,
+}
+impl Drop for ReadGuard<'_> {
+    fn drop(&mut self) {
+        self.state.scope(|state| state.readers[self.index] -= 1);
+    }
+}
+
+/// Releases a write access to the inactive buffer slot on drop
+struct WriteGuard<'a> {
+    /// The bookkeeping
+    state: &'a InterruptCell<BufferState>,
+}
+impl Drop for WriteGuard<'_> {
+    fn drop(&mut self) {
+        self.state.scope(|state| state.writing = false);
+    }
+}
+
+/// A double-buffered, thread-local cell where writers fill the inactive buffer and readers access the latest snapshot
+///
+/// # Note
+/// Only the bookkeeping is done within an interrupt-safe critical section; the buffers themselves are filled and read
+/// outside of it. This allows e.g. an interrupt handler to publish new data while the main loop is still processing the
+/// previous snapshot, without the interrupt being delayed by the processing.
+///
+/// # Warning
+/// This cell must not be accessed from another thread; doing so will raise a panic.
+pub struct DoubleBufferCell<T> {
+    /// The bookkeeping
+    state: InterruptCell<BufferState>,
+    /// The buffer slots
+    slots: [UnsafeCell<T>; 2],
+}
+impl<T> DoubleBufferCell<T> {
+    /// Creates a new double-buffered cell with the active buffer `front` and the inactive buffer `back`
+    pub const fn new_with_threadid(front: T, back: T, thread_id: usize) -> Self {
+        let state = BufferState { active: 0, readers: [0; 2], writing: false };
+        Self {
+            state: InterruptCell::new_with_threadid(state, thread_id),
+            slots: [UnsafeCell::new(front), UnsafeCell::new(back)],
+        }
+    }
+
+    /// Creates a new double-buffered cell with the active buffer `front` and the inactive buffer `back`
+    pub fn new(front: T, back: T) -> Self {
+        let state = BufferState { active: 0, readers: [0; 2], writing: false };
+        Self { state: InterruptCell::new(state), slots: [UnsafeCell::new(front), UnsafeCell::new(back)] }
+    }
+
+    /// Fills the inactive buffer in place via `fill` and makes it the active buffer
+    ///
+    /// Returns `false` without calling `fill` if the inactive buffer is still being read or if another write is in
+    /// progress (e.g. if an interrupt handler writes while the main loop is writing).
+    ///
+    /// # Panic
+    /// This function will panic if called from another thread
+    pub fn write_with<F>(&self, fill: F) -> bool
+    where
+        F: FnOnce(&mut T),
+    {
+        // Claim the inactive slot
+        let claimed = self.state.scope(|state| {
+            let inactive = state.active ^ 1;
+            if state.writing || state.readers[inactive] > 0 {
+                return None;
+            }
+            state.writing = true;
+            Some(inactive)
+        });
+        let Some(index) = claimed else {
+            return false;
+        };
+
+        // Fill the slot; the claim ensures that there are no other references to it
+        let _guard = WriteGuard { state: &self.state };
+        let slot_ptr = self.slots[index].get();
+        fill(unsafe { &mut *slot_ptr });
+
+        // Publish the slot
+        self.state.scope(|state| state.active = index);
+        true
+    }
+
+    /// Writes the value into the inactive buffer and makes it the active buffer, or returns the value as error if the
+    /// inactive buffer is still being read or if another write is in progress
+    ///
+    /// # Panic
+    /// This function will panic if called from another thread
+    pub fn write(&self, value: T) -> Result<(), T> {
+        // The value is only taken if the inactive slot could be claimed
+        let mut value = Some(value);
+        self.write_with(|slot| {
+            if let Some(value) = value.take() {
+                *slot = value;
+            }
+        });
+        value.map_or(Ok(()), Err)
+    }
+
+    /// Provides scoped access to the latest snapshot in the active buffer
+    ///
+    /// # Panic
+    /// This function will panic if called from another thread
+    pub fn read<F, FR>(&self, scope: F) -> FR
+    where
+        F: FnOnce(&T) -> FR,
+    {
+        // Register as reader of the active slot, so that writers don't overwrite it while we read
+        let index = self.state.scope(|state| {
+            state.readers[state.active] += 1;
+            state.active
+        });
+
+        // Call the scope; the registration ensures that there are no mutable references to the slot
+        let _guard = ReadGuard { state: &self.state, index };
+        let slot_ptr = self.slots[index].get();
+        scope(unsafe { &*slot_ptr })
+    }
+}
+unsafe impl<T> Sync for DoubleBufferCell<T>
+where
+    T: Send + Sync,
+{
+    // Marker trait, no members to implement
+}
@@ -0,0 +1,50 @@
+//! Errors that can occur when accessing a cell
+
+use core::fmt::{self, Display, Formatter};
+
+/// An error that indicates that a cell cannot be accessed from the current context
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AccessError {
+    /// The cell is bound to another thread
+    WrongThread {
+        /// The ID of the thread the cell is bound to
+        expected: usize,
+        /// The ID of the current thread
+        actual: usize,
+    },
+    /// The cell cannot be accessed from an interrupt context
+    InterruptContext,
+    /// The lazily initialized value has not been initialized yet
+    NotInitialized,
+    /// The cell is already being accessed
+    Reentrant,
+    /// The cell has been poisoned by a panic during a previous access
+    Poisoned,
+}
+impl Display for AccessError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Self::WrongThread { expected, actual } => {
+                write!(f, "cannot access cell of thread {expected} from thread {actual}")
+            }
+            Self::InterruptContext => write!(f, "cannot access cell from an interrupt handler"),
+            Self::NotInitialized => write!(f, "cannot access cell which has not been initialized yet"),
+            Self::Reentrant => write!(f, "cannot access cell which is already being accessed"),
+            Self::Poisoned => write!(f, "cannot access cell which has been poisoned by a panic"),
+        }
+    }
+}
+
+/// An error that indicates that a versioned cell has been modified since the expected generation
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VersionMismatch {
+    /// The expected generation
+    pub expected: u64,
+    /// The current generation
+    pub actual: u64,
+}
+impl Display for VersionMismatch {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "cell has been modified (expected generation {}, found {})", self.expected, self.actual)
+    }
+}
@@ -0,0 +1,144 @@
+//! A wrapper that records access statistics for a cell
+
+use crate::{
+    traits::{Scope, TryScope},
+    AccessError, SharedCell,
+};
+
+/// The access statistics of an instrumented cell
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CellStats {
+    /// The amount of scope invocations
+    pub calls: u32,
+    /// The total duration of all scope invocations in clock ticks
+    pub total_time: u64,
+    /// The maximum duration of a single scope invocation in clock ticks
+    pub max_time: u32,
+}
+impl CellStats {
+    /// Creates a new, empty statistics record
+    pub const fn new() -> Self {
+        Self { calls: 0, total_time: 0, max_time: 0 }
+    }
+
+    /// The average duration of a scope invocation in clock ticks, or `None` if there have not been any invocations yet
+    pub fn average_time(&self) -> Option<u64> {
+        self.total_time.checked_div(self.calls as u64)
+    }
+
+    /// Records a scope invocation with the given duration
+    fn record(&mut self, duration: u32) {
+        self.calls = self.calls.saturating_add(1);
+        self.total_time = self.total_time.saturating_add(duration as u64);
+        self.max_time = self.max_time.max(duration);
+    }
+}
+
+/// A wrapper that records access statistics for a cell
+///
+/// # Note
+/// The durations are measured with the user-provided clock, which is usually a cycle counter (e.g. the DWT cycle
+/// counter on Cortex-M) or a free-running timer. The clock may wrap around, but a single invocation must not take
+/// longer than a full clock period.
+pub struct InstrumentedCell<C>
+where
+    C: ?Sized,
+{
+    /// The clock to measure the durations with
+    clock: fn() -> u32,
+    /// The collected statistics
+    stats: SharedCell<CellStats>,
+    /// The wrapped cell
+    inner: C,
+}
+impl<C> InstrumentedCell<C> {
+    /// Creates a new instrumented cell that measures the durations with `clock`
+    pub const fn new(inner: C, clock: fn() -> u32) -> Self {
+        Self { clock, stats: SharedCell::new(CellStats::new()), inner }
+    }
+}
+impl<C> InstrumentedCell<C>
+where
+    C: ?Sized,
+{
+    /// Provides scoped access to the underlying value of the wrapped cell and records the invocation
+    ///
+    /// # Panic
+    /// This function will panic if the wrapped cell panics
+    pub fn scope<T, F, FR>(&self, scope: F) -> FR
+    where
+        T: ?Sized,
+        C: Scope<T>,
+        F: FnOnce(&mut T) -> FR,
+    {
+        // Measure the invocation and record it
+        let start = (self.clock)();
+        let result = self.inner.scope(scope);
+        self.record(start);
+        result
+    }
+
+    /// Provides scoped access to the underlying value of the wrapped cell if it is accessible from the current context
+    /// and records the invocation
+    pub fn try_scope<T, F, FR>(&self, scope: F) -> Result<FR, AccessError>
+    where
+        T: ?Sized,
+        C: TryScope<T>,
+        F: FnOnce(&mut T) -> FR,
+    {
+        // Measure the invocation and record it
+        let start = (self.clock)();
+        let result = self.inner.try_scope(scope)?;
+        self.record(start);
+        Ok(result)
+    }
+
+    /// Gets the collected statistics
+    pub fn stats(&self) -> CellStats {
+        self.stats.scope(|stats| *stats)
+    }
+
+    /// Resets the collected statistics
+    pub fn reset_stats(&self) {
+        self.stats.scope(|stats| *stats = CellStats::new())
+    }
+
+    /// Gets the wrapped cell
+    pub const fn inner(&self) -> &C {
+        &self.inner
+    }
+
+    /// Records an invocation that started at `start`
+    fn record(&self, start: u32) {
+        let duration = (self.clock)().wrapping_sub(start);
+        self.stats.scope(|stats| stats.record(duration))
+    }
+}
+impl<T, C> Scope<T> for InstrumentedCell<C>
+where
+    T: ?Sized,
+    C: ?Sized + Scope<T>,
+{
+    fn scope<F, FR>(&self, scope: F) -> FR
+    where
+        F: FnOnce(&mut T) -> FR,
+    {
+        Self::scope(self, scope)
+    }
+
+    unsafe fn as_ref_unchecked(&self) -> &T {
+        self.inner.as_ref_unchecked()
+    }
+}
+impl<T, C> TryScope<T> for InstrumentedCell<C>
+where
+    T: ?Sized,
+    C: ?Sized + TryScope<T>,
+{
+    fn try_scope<F, FR>(&self, scope: F) -> Result<FR, AccessError>
+    where
+        F: FnOnce(&mut T) -> FR,
+    {
+        Self::try_scope(self, scope)
+    }
+}
@@ -1,17 +1,77 @@
 //! A lazily instantiated cell
 
-use core::cell::UnsafeCell;
+#[cfg(debug_assertions)]
+use core::cell::Cell;
+use core::{cell::UnsafeCell, mem};
+
+/// An initializer for a lazy cell
+pub trait Initializer<T> {
+    /// Consumes the initializer and creates the value
+    fn initialize(self) -> T;
+}
+impl<T, F> Initializer<T> for F
+where
+    F: FnOnce() -> T,
+{
+    fn initialize(self) -> T {
+        self()
+    }
+}
+
+/// An initializer that passes a context to the initializer function
+pub struct WithContext<F, C> {
+    /// The initializer function
+    init: F,
+    /// The context to pass to the initializer function
+    context: C,
+}
+impl<T, F, C> Initializer<T> for WithContext<F, C>
+where
+    F: FnOnce(C) -> T,
+{
+    fn initialize(self) -> T {
+        (self.init)(self.context)
+    }
+}
+
+/// The state of a lazy cell
+enum LazyState<T, I> {
+    /// The value has not been initialized yet
+    Uninit(I),
+    /// The value has been initialized
+    Init(T),
+    /// The initializer has panicked
+    Poisoned,
+}
 
 /// A lazily instantiated cell
 pub struct LazyCell<T, I = fn() -> T> {
-    /// A tuple containing the initializer and the value
-    inner: UnsafeCell<(Option<I>, Option<T>)>,
+    /// Either the initializer or the value
+    inner: UnsafeCell<LazyState<T, I>>,
+    /// The amount of scope invocations (debug builds only)
+    #[cfg(debug_assertions)]
+    scope_count: Cell<usize>,
 }
 impl<T, I> LazyCell<T, I> {
     /// Creates a new lazy cell with the given initializer
     pub const fn new(init: I) -> Self {
-        let value = (Some(init), None);
-        Self { inner: UnsafeCell::new(value) }
+        let state = LazyState::Uninit(init);
+        Self {
+            inner: UnsafeCell::new(state),
+            #[cfg(debug_assertions)]
+            scope_count: Cell::new(0),
+        }
+    }
+
+    /// Gets the amount of scope invocations, or `None` in release builds where the invocations are not counted
+    ///
+    /// # Note
+    /// This is intended for debugging, e.g. to detect lazy cells that are never accessed or accessed unexpectedly often.
+    pub fn scope_count(&self) -> Option<usize> {
+        #[cfg(debug_assertions)]
+        return Some(self.scope_count.get());
+        #[cfg(not(debug_assertions))]
+        return None;
     }
 
     /// Provides scoped access to the underlying value, initializes it if necessary
@@ -22,35 +82,165 @@ impl<T, I> LazyCell<T, I> {
     #[inline]
     pub unsafe fn scope<F, FR>(&self, scope: F) -> FR
     where
-        I: FnOnce() -> T,
+        I: Initializer<T>,
         F: FnOnce(&mut T) -> FR,
     {
+        // Count the invocation
+        #[cfg(debug_assertions)]
+        self.scope_count.set(self.scope_count.get().saturating_add(1));
+
         // Get the inner state
         let inner_ptr = self.inner.get();
-        let (init, value) = inner_ptr.as_mut().expect("unexpected NULL pointer inside cell");
+        let state = inner_ptr.as_mut().expect("unexpected NULL pointer inside cell");
 
-        // Initialize the value if necessary
-        if let Some(init) = init.take() {
-            let value_ = init();
-            *value = Some(value_);
+        // Initialize the value if necessary; the initializer is taken first, so a panicking initializer leaves the cell
+        // in the poisoned state
+        if let LazyState::Uninit(_) = state {
+            let LazyState::Uninit(init) = mem::replace(state, LazyState::Poisoned) else {
+                unreachable!("uninitialized cell has no initializer");
+            };
+            *state = LazyState::Init(init.initialize());
         }
 
         // Take the initialized value
-        let Some(value) = value.as_mut() else {
-            unreachable!("initialized cell has not value");
+        let LazyState::Init(value) = state else {
+            panic!("cannot access lazy cell which has been poisoned by a panicking initializer");
         };
 
         // Call the scope
         scope(value)
     }
 
+    /// Provides scoped access to the underlying value if it has already been initialized, without running the
+    /// initializer otherwise
+    ///
+    /// # Safety
+    /// This function provides unchecked, mutable access to the underlying value, so incorrect use of this function may
+    /// lead to race conditions or undefined behavior.
+    #[inline]
+    pub unsafe fn scope_if_initialized<F, FR>(&self, scope: F) -> Option<FR>
+    where
+        F: FnOnce(&mut T) -> FR,
+    {
+        // Get the inner state
+        let inner_ptr = self.inner.get();
+        let state = inner_ptr.as_mut().expect("unexpected NULL pointer inside cell");
+
+        // Call the scope if the value has been initialized
+        match state {
+            LazyState::Init(value) => Some(scope(value)),
+            _ => None,
+        }
+    }
+
+    /// Gets a reference to the underlying value if it has already been initialized, without running the initializer
+    /// otherwise
+    ///
+    /// # Safety
+    /// This function provides unchecked access to the underlying value; the caller must ensure that the value is
+    /// neither initialized nor modified from another context (e.g. via a scope) for as long as the returned reference
+    /// is alive.
+    #[inline]
+    pub unsafe fn peek(&self) -> Option<&T> {
+        let inner_ptr = self.inner.get();
+        let state = inner_ptr.as_ref().expect("unexpected NULL pointer inside cell");
+        match state {
+            LazyState::Init(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Gets a mutable reference to the underlying value if it has already been initialized, without running the
+    /// initializer otherwise
+    #[inline]
+    pub fn peek_mut(&mut self) -> Option<&mut T> {
+        match self.inner.get_mut() {
+            LazyState::Init(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Tests whether the value has already been initialized, without running the initializer otherwise
+    ///
+    /// # Safety
+    /// This function provides unchecked access to the underlying state, so incorrect use of this function may lead to
+    /// race conditions or undefined behavior.
+    #[inline]
+    pub unsafe fn is_initialized(&self) -> bool {
+        let inner_ptr = self.inner.get();
+        let state = inner_ptr.as_ref().expect("unexpected NULL pointer inside cell");
+        matches!(state, LazyState::Init(_))
+    }
+
+    /// Provides scoped access to the underlying value, initializes it if necessary
+    #[inline]
+    pub fn scope_mut<F, FR>(&self, scope: F) -> FR
+    where
+        I: Initializer<T>,
+        F: FnOnce(&mut T) -> FR,
+    {
+        unsafe { self.scope(scope) }
+    }
+}
+impl<T, F, C> LazyCell<T, WithContext<F, C>> {
+    /// Creates a new lazy cell with the given initializer that consumes `context` on initialization
+    pub const fn new_with_context(init: F, context: C) -> Self {
+        Self::new(WithContext { init, context })
+    }
+}
+
+/// A lazily instantiated cell that can be reset to re-run its initializer
+pub struct ResettableLazyCell<T, I = fn() -> T> {
+    /// The initializer
+    init: I,
+    /// The value if it has been initialized
+    value: UnsafeCell<Option<T>>,
+}
+impl<T, I> ResettableLazyCell<T, I> {
+    /// Creates a new resettable lazy cell with the given initializer
+    pub const fn new(init: I) -> Self {
+        Self { init, value: UnsafeCell::new(None) }
+    }
+
+    /// Provides scoped access to the underlying value, initializes it if necessary
+    ///
+    /// # Safety
+    /// This function provides unchecked, mutable access to the underlying value, so incorrect use of this function may
+    /// lead to race conditions or undefined behavior.
+    #[inline]
+    pub unsafe fn scope<F, FR>(&self, scope: F) -> FR
+    where
+        I: Fn() -> T,
+        F: FnOnce(&mut T) -> FR,
+    {
+        // Get the value and initialize it if necessary
+        let value_ptr = self.value.get();
+        let value = value_ptr.as_mut().expect("unexpected NULL pointer inside cell");
+        let value = value.get_or_insert_with(&self.init);
+
+        // Call the scope
+        scope(value)
+    }
+
     /// Provides scoped access to the underlying value, initializes it if necessary
     #[inline]
     pub fn scope_mut<F, FR>(&self, scope: F) -> FR
     where
-        I: FnOnce() -> T,
+        I: Fn() -> T,
         F: FnOnce(&mut T) -> FR,
     {
         unsafe { self.scope(scope) }
     }
+
+    /// Drops the current value if any, so that the next scope call will re-run the initializer
+    ///
+    /// # Safety
+    /// This function provides unchecked, mutable access to the underlying value, so incorrect use of this function may
+    /// lead to race conditions or undefined behavior.
+    #[inline]
+    pub unsafe fn reset(&self) {
+        let value_ptr = self.value.get();
+        let value = value_ptr.as_mut().expect("unexpected NULL pointer inside cell");
+        *value = None;
+    }
 }
@@ -1,13 +1,38 @@
 #![no_std]
 #![doc = include_str!("../README.md")]
 
-mod runtime;
+mod macros;
 
+pub mod atomic;
+pub mod chain;
+pub mod double_buffer;
+pub mod error;
+#[cfg(feature = "instrument")]
+pub mod instrument;
 pub mod lazy;
+pub mod notify;
+pub mod oncecell;
+pub mod queue;
+pub mod runtime;
 pub mod safecells;
+pub mod token;
+pub mod tracing;
+pub mod traits;
+pub mod versioned;
+pub mod volatile;
+pub mod watch;
 
-// Re-export the cells
+// Re-export the cells, traits and error type
 pub use crate::{
-    lazy::LazyCell,
+    atomic::AtomicCell,
+    double_buffer::DoubleBufferCell,
+    error::{AccessError, VersionMismatch},
+    lazy::{LazyCell, ResettableLazyCell},
+    notify::NotifyingCell,
+    oncecell::WriteOnceCell,
     safecells::{interrupt::InterruptCell, local::LocalCell, shared::SharedCell},
+    token::CriticalSectionToken,
+    traits::{Scope, TryScope},
+    versioned::VersionedCell,
+    watch::WatchCell,
 };
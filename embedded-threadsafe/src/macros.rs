@@ -0,0 +1,59 @@
+//! Macros to declare static cells
+
+/// Declares a static [`LocalCell`](crate::LocalCell) that lazily initializes its value on first access
+///
+/// The value can be accessed via [`LocalCell::lazy_scope`](crate::LocalCell::lazy_scope).
+///
+/// # Example
+/// ```rust
+/// use embedded_threadsafe::declare_local_cell;
+///
+/// // A counter that belongs to the first core
+/// declare_local_cell!(pub COUNTER, u32, || 7, 0);
+/// ```
+#[macro_export]
+macro_rules! declare_local_cell {
+    ($vis:vis $name:ident, $type:ty, $init:expr, $thread_id:expr) => {
+        $vis static $name: $crate::LocalCell<$crate::LazyCell<$type>> =
+            $crate::LocalCell::new_with_threadid($crate::LazyCell::new($init), $thread_id);
+    };
+}
+
+/// Declares a static [`SharedCell`](crate::SharedCell) that lazily initializes its value on first access
+///
+/// The value can be accessed via [`SharedCell::lazy_scope`](crate::SharedCell::lazy_scope).
+///
+/// # Example
+/// ```rust
+/// use embedded_threadsafe::declare_shared_cell;
+///
+/// // A counter that can be shared across all threads and interrupt contexts
+/// declare_shared_cell!(pub COUNTER, u32, || 7);
+/// ```
+#[macro_export]
+macro_rules! declare_shared_cell {
+    ($vis:vis $name:ident, $type:ty, $init:expr) => {
+        $vis static $name: $crate::SharedCell<$crate::LazyCell<$type>> =
+            $crate::SharedCell::new($crate::LazyCell::new($init));
+    };
+}
+
+/// Asserts at compile time that a thread ID is smaller than the amount of cores of the platform
+///
+/// Platform crates for systems with a fixed amount of cores export this amount as `MAX_CORES` (e.g.
+/// `embedded_threadsafe_rp2040::MAX_CORES`), so a thread ID for a cell can be verified against it.
+///
+/// # Example
+/// ```rust
+/// use embedded_threadsafe::static_assert_thread_id;
+///
+/// // Ensure that the second core exists on a dual-core platform
+/// const MAX_CORES: usize = 2;
+/// static_assert_thread_id!(1, MAX_CORES);
+/// ```
+#[macro_export]
+macro_rules! static_assert_thread_id {
+    ($thread_id:expr, $max_cores:expr) => {
+        const _: () = assert!($thread_id < $max_cores, "THREAD_ID exceeds platform MAX_CORES");
+    };
+}
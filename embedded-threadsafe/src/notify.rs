@@ -0,0 +1,84 @@
+//! A shared cell that allows to wait for updates
+
+use crate::{traits::Scope, SharedCell};
+use core::{
+    fmt::{self, Debug, Formatter},
+    hint,
+    sync::atomic::{AtomicU32, Ordering},
+};
+
+/// A shared cell that allows to wait for updates
+///
+/// # Note
+/// Every update increments a sequence number, so a worker can wait until the sequence number differs from the last one
+/// it has seen.
+pub struct NotifyingCell<T> {
+    /// The wrapped value
+    inner: SharedCell<T>,
+    /// The sequence number of the last update
+    sequence: AtomicU32,
+}
+impl<T> NotifyingCell<T> {
+    /// Creates a new cell
+    pub const fn new(value: T) -> Self {
+        Self { inner: SharedCell::new(value), sequence: AtomicU32::new(0) }
+    }
+
+    /// Updates the underlying value and notifies all waiters
+    ///
+    /// # Panic
+    /// This function will panic if the cell is already locked or has been poisoned by a panicking scope
+    pub fn update<F, FR>(&self, update: F) -> FR
+    where
+        F: FnOnce(&mut T) -> FR,
+    {
+        self.inner.scope(|value| {
+            // Perform the update and bump the sequence number; we are in a critical section, so we don't need an atomic
+            // read-modify-write operation which is not available on all targets
+            let result = update(value);
+            let sequence = self.sequence.load(Ordering::Relaxed);
+            self.sequence.store(sequence.wrapping_add(1), Ordering::Release);
+            result
+        })
+    }
+
+    /// Provides scoped read-only access to the underlying value
+    ///
+    /// # Panic
+    /// This function will panic if the cell is already locked or has been poisoned by a panicking scope
+    pub fn read<F, FR>(&self, scope: F) -> FR
+    where
+        F: FnOnce(&T) -> FR,
+    {
+        self.inner.with_ref(scope)
+    }
+
+    /// The sequence number of the last update
+    pub fn sequence(&self) -> u32 {
+        self.sequence.load(Ordering::Acquire)
+    }
+
+    /// Busy-waits until the sequence number differs from `last_sequence` and returns the new sequence number
+    ///
+    /// # Warning
+    /// This function spins until the value is updated, so it must not be called from a context that would prevent the
+    /// update (e.g. from an interrupt handler with a higher priority than the updating context).
+    pub fn wait_for_change(&self, last_sequence: u32) -> u32 {
+        loop {
+            // Return the sequence number if it has changed
+            let sequence = self.sequence();
+            if sequence != last_sequence {
+                return sequence;
+            }
+            hint::spin_loop();
+        }
+    }
+}
+impl<T> Debug for NotifyingCell<T>
+where
+    T: Debug,
+{
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct("NotifyingCell").field("inner", &self.inner).field("sequence", &self.sequence()).finish()
+    }
+}
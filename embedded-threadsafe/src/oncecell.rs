@@ -0,0 +1,95 @@
+//! A cell that can be written once from any context and read from all contexts thereafter
+
+use crate::runtime;
+use core::{
+    cell::UnsafeCell,
+    fmt::{self, Debug, Formatter},
+    mem::MaybeUninit,
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+/// A cell that can be written once from any context and read from all contexts thereafter
+///
+/// # Note
+/// Writing the value requires a critical section, but reading it after initialization only requires an atomic load.
+pub struct WriteOnceCell<T> {
+    /// The value if it has been set
+    inner: UnsafeCell<MaybeUninit<T>>,
+    /// Whether the value has been set or not
+    initialized: AtomicBool,
+}
+impl<T> WriteOnceCell<T> {
+    /// Creates a new, empty cell
+    pub const fn new() -> Self {
+        Self { inner: UnsafeCell::new(MaybeUninit::uninit()), initialized: AtomicBool::new(false) }
+    }
+
+    /// Sets the value if it has not been set yet, or gives it back otherwise
+    pub fn set(&self, value: T) -> Result<(), T> {
+        // Create mutable slots to transfer state to/from the closure and create the caller
+        let mut value = Some(value);
+        let mut call_set = || {
+            // Ensure that the value has not been set yet
+            if self.initialized.load(Ordering::Relaxed) {
+                return;
+            }
+
+            // Consume and write the value, and publish it afterwards
+            let value = value.take().expect("missing value");
+            let inner_ptr = self.inner.get();
+            let slot = unsafe { inner_ptr.as_mut() }.expect("unexpected NULL pointer inside cell");
+            slot.write(value);
+            self.initialized.store(true, Ordering::Release);
+        };
+
+        // Run the implementation in a threadsafe context and give the value back if it has not been consumed
+        unsafe { runtime::_runtime_threadsafe_e0LtH0x3(&mut call_set) };
+        match value {
+            Some(value) => Err(value),
+            None => Ok(()),
+        }
+    }
+
+    /// Gets the value if it has been set
+    pub fn get(&self) -> Option<&T> {
+        // Ensure that the value has been set
+        if !self.initialized.load(Ordering::Acquire) {
+            return None;
+        }
+
+        // The value is never modified again after it has been set
+        let inner_ptr = self.inner.get();
+        let slot = unsafe { inner_ptr.as_ref() }.expect("unexpected NULL pointer inside cell");
+        Some(unsafe { slot.assume_init_ref() })
+    }
+}
+impl<T> Default for WriteOnceCell<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl<T> Debug for WriteOnceCell<T>
+where
+    T: Debug,
+{
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self.get() {
+            Some(value) => f.debug_tuple("WriteOnceCell").field(value).finish(),
+            None => f.debug_tuple("WriteOnceCell").field(&"<uninitialized>").finish(),
+        }
+    }
+}
+impl<T> Drop for WriteOnceCell<T> {
+    fn drop(&mut self) {
+        // Drop the value if it has been set
+        if *self.initialized.get_mut() {
+            unsafe { self.inner.get_mut().assume_init_drop() };
+        }
+    }
+}
+unsafe impl<T> Sync for WriteOnceCell<T>
+where
+    T: Send + Sync,
+{
+    // Marker trait, no members to implement
+}
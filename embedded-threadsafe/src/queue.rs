@@ -0,0 +1,107 @@
+//! A fixed-size single-producer single-consumer queue to pass values from interrupt handlers to the owning thread
+
+use crate::InterruptCell;
+
+/// Declares a static [`SpscQueue`] wrapped into an [`InterruptCell`]
+///
+/// The thread ID is optional and defaults to `0` (i.e. usually the first core on bare-metal systems).
+///
+/// # Example
+/// ```rust
+/// use embedded_threadsafe::spsc_queue;
+///
+/// // A queue for up to 64 bytes which belongs to the first core and a queue for up to 8 words on the second core
+/// spsc_queue!(RX_QUEUE, u8, 64);
+/// spsc_queue!(pub EVENT_QUEUE, u32, 8, 1);
+/// ```
+#[macro_export]
+macro_rules! spsc_queue {
+    ($vis:vis $name:ident, $type:ty, $capacity:expr) => {
+        $crate::spsc_queue!($vis $name, $type, $capacity, 0);
+    };
+    ($vis:vis $name:ident, $type:ty, $capacity:expr, $thread_id:expr) => {
+        $vis static $name: $crate::InterruptCell<$crate::queue::SpscQueue<$type, { $capacity }>> =
+            $crate::InterruptCell::new_with_threadid($crate::queue::SpscQueue::new(), $thread_id);
+    };
+}
+
+/// A fixed-size single-producer single-consumer ring buffer
+pub struct SpscQueue<T, const N: usize> {
+    /// The ring buffer slots
+    slots: [Option<T>; N],
+    /// The index of the oldest element
+    head: usize,
+    /// The amount of elements in the queue
+    len: usize,
+}
+impl<T, const N: usize> SpscQueue<T, N> {
+    /// Creates a new, empty queue
+    pub const fn new() -> Self {
+        Self { slots: [const { None }; N], head: 0, len: 0 }
+    }
+
+    /// Appends a value to the queue, or returns the value as error if the queue is full
+    pub fn push(&mut self, value: T) -> Result<(), T> {
+        // Ensure that we have a free slot
+        if self.len == N {
+            return Err(value);
+        }
+
+        // Insert the value behind the last element
+        let index = (self.head + self.len) % N;
+        self.slots[index] = Some(value);
+        self.len += 1;
+        Ok(())
+    }
+
+    /// Removes the oldest value from the queue, or returns `None` if the queue is empty
+    pub fn pop(&mut self) -> Option<T> {
+        // Ensure that we have an element
+        if self.len == 0 {
+            return None;
+        }
+
+        // Take the oldest element and advance the head
+        let value = self.slots[self.head].take();
+        self.head = (self.head + 1) % N;
+        self.len -= 1;
+        value
+    }
+
+    /// The amount of elements in the queue
+    pub const fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Whether the queue is empty or not
+    pub const fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Whether the queue is full or not
+    pub const fn is_full(&self) -> bool {
+        self.len == N
+    }
+}
+impl<T, const N: usize> Default for SpscQueue<T, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl<T, const N: usize> InterruptCell<SpscQueue<T, N>> {
+    /// Appends a value to the underlying queue, or returns the value as error if the queue is full
+    ///
+    /// # Panic
+    /// This function will panic if called from another thread
+    pub fn push(&self, value: T) -> Result<(), T> {
+        self.scope(|queue| queue.push(value))
+    }
+
+    /// Removes the oldest value from the underlying queue, or returns `None` if the queue is empty
+    ///
+    /// # Panic
+    /// This function will panic if called from another thread
+    pub fn pop(&self) -> Option<T> {
+        self.scope(|queue| queue.pop())
+    }
+}
@@ -1,4 +1,4 @@
-//! Defines requires runtime-specific function stubs
+//! Defines requires runtime-specific function stubs and a validator to self-test runtime implementations
 
 extern "Rust" {
     /// Ensures that `code` is run exclusively, without being subject to multicore/-thread race conditions or interrupts
@@ -19,3 +19,73 @@ extern "Rust" {
     /// Tests whether we are currently in an interrupt context or not
     pub(crate) fn _runtime_isinterrupted_v5tnnoC7() -> bool;
 }
+
+/// A validator to self-test a platform runtime implementation
+///
+/// # Safety
+/// The provided methods call into the runtime, so this trait must only be implemented if the runtime functions are
+/// provided by the linked platform crate.
+pub unsafe trait RuntimeValidator {
+    /// Tests whether the thread ID is the same if it is queried multiple times from the same context
+    fn validate_thread_id_consistency(&self) -> bool {
+        let first = unsafe { _runtime_threadid_ZhZIZBv4() };
+        let second = unsafe { _runtime_threadid_ZhZIZBv4() };
+        first == second
+    }
+
+    /// Tests whether threadsafe critical sections can be nested and whether the thread ID is stable within them
+    ///
+    /// # Warning
+    /// If the critical section is not re-entrant, the nested section deadlocks and this function never returns; the
+    /// runtime offers no way to probe its lock without blocking. A hanging validation therefore indicates a
+    /// non-re-entrant critical section.
+    fn validate_critical_section_reentrancy(&self) -> bool {
+        // Enter two nested critical sections
+        let thread_id = unsafe { _runtime_threadid_ZhZIZBv4() };
+        let mut inner_thread_id = None;
+        let mut outer = || {
+            let mut inner = || inner_thread_id = Some(unsafe { _runtime_threadid_ZhZIZBv4() });
+            unsafe { _runtime_threadsafe_e0LtH0x3(&mut inner) };
+        };
+        unsafe { _runtime_threadsafe_e0LtH0x3(&mut outer) };
+
+        // Ensure that the inner scope has been called from the same thread
+        inner_thread_id == Some(thread_id)
+    }
+
+    /// Tests whether interrupt-safe sections can be nested and whether the thread ID is stable within them
+    ///
+    /// # Warning
+    /// If the interrupt-safe section is not re-entrant, the nested section deadlocks and this function never returns.
+    fn validate_interruptsafe_reentrancy(&self) -> bool {
+        // Enter two nested interrupt-safe sections
+        let thread_id = unsafe { _runtime_threadid_ZhZIZBv4() };
+        let mut inner_thread_id = None;
+        let mut outer = || {
+            let mut inner = || inner_thread_id = Some(unsafe { _runtime_threadid_ZhZIZBv4() });
+            unsafe { _runtime_interruptsafe_1l52Ge5e(&mut inner) };
+        };
+        unsafe { _runtime_interruptsafe_1l52Ge5e(&mut outer) };
+
+        // Ensure that the inner scope has been called from the same thread
+        inner_thread_id == Some(thread_id)
+    }
+}
+
+/// Exercises all runtime invariants checked by `validator` and returns whether all of them hold
+///
+/// # Note
+/// This function should be called from a thread context, as it is intended to be used by platform crates in their
+/// integration tests.
+///
+/// # Warning
+/// A runtime with non-re-entrant sections makes this function hang instead of returning `false`, so integration tests
+/// should run it with a timeout.
+pub fn run_runtime_validation<V>(validator: &V) -> bool
+where
+    V: RuntimeValidator,
+{
+    validator.validate_thread_id_consistency()
+        && validator.validate_critical_section_reentrancy()
+        && validator.validate_interruptsafe_reentrancy()
+}
@@ -1,54 +1,182 @@
 //! A fast, thread-local cell that can be safely shared accross interrupt contexts
 
-use crate::{runtime, LazyCell};
+#[cfg(debug_assertions)]
+use crate::tracing::ScopeTrace;
+use crate::{
+    error::AccessError,
+    lazy::Initializer,
+    runtime,
+    safecells::LockFlag,
+    traits::{Scope, TryScope},
+    LazyCell,
+};
 use core::{
-    cell::UnsafeCell,
-    fmt::{self, Debug, Formatter},
+    any,
+    cell::{Cell, UnsafeCell},
+    fmt::{self, Debug, Display, Formatter},
+    hash::{Hash, Hasher},
+    ptr,
 };
 
 /// A fast, thread-local cell that can be safely shared accross interrupt contexts
 ///
 /// # Warning
 /// This cell must not be accessed from another thread; doing so will raise a panic.
-pub struct InterruptCell<T> {
-    /// The wrapped value
-    inner: UnsafeCell<T>,
+/// If the crate is built with `--cfg embedded_threadsafe_unsafe_disable_runtime_checks` (e.g. via `RUSTFLAGS`), the
+/// check is skipped and such an access is undefined behavior.
+///
+/// # Example
+/// ```rust
+/// use embedded_threadsafe::InterruptCell;
+///
+/// // A tick counter that belongs to the first core and is incremented by the timer interrupt
+/// static TICKS: InterruptCell<u64> = InterruptCell::new_with_threadid(0, 0);
+///
+/// fn on_timer_interrupt() {
+///     TICKS.scope(|ticks| *ticks += 1);
+/// }
+/// ```
+pub struct InterruptCell<T>
+where
+    T: ?Sized,
+{
     /// The associated thread ID
     thread_id: usize,
+    /// Whether the cell is currently locked
+    locked: Cell<bool>,
+    /// The wrapped value
+    inner: UnsafeCell<T>,
 }
 impl<T> InterruptCell<T> {
-    /// Creates a new thread-local cell
+    /// Creates a new thread-local cell that is bound to the thread with the ID `thread_id`
+    ///
+    /// # Note
+    /// This is the `const` constructor, so it can be used to initialize a `static`.
     pub const fn new_with_threadid(value: T, thread_id: usize) -> Self {
-        Self { inner: UnsafeCell::new(value), thread_id }
+        Self { thread_id, locked: Cell::new(false), inner: UnsafeCell::new(value) }
     }
 
-    /// Creates a new thread-local cell
+    /// Creates a new thread-local cell that is bound to the current thread
+    ///
+    /// # Note
+    /// The thread ID is queried at runtime, so this constructor cannot be used to initialize a `static`; use
+    /// [`Self::new_with_threadid`] instead.
     pub fn new(value: T) -> Self {
         // Get the thread ID and init self
         let thread_id = unsafe { runtime::_runtime_threadid_ZhZIZBv4() };
         Self::new_with_threadid(value, thread_id)
     }
-
+}
+impl<T> InterruptCell<T>
+where
+    T: ?Sized,
+{
     /// Provides scoped access to the underlying value
     ///
     /// # Panic
-    /// This function will panic if called from another thread or interrupt context
+    /// This function will panic if called from another thread, or if the cell is already locked
+    ///
+    /// # Warning
+    /// If the runtime checks are disabled via `--cfg embedded_threadsafe_unsafe_disable_runtime_checks`, accessing
+    /// the cell from another thread is undefined behavior.
     pub fn scope<F, FR>(&self, scope: F) -> FR
     where
         F: FnOnce(&mut T) -> FR,
     {
-        // Ensure that we access this from the correct thread ID
-        let thread_id = unsafe { runtime::_runtime_threadid_ZhZIZBv4() };
-        assert_eq!(thread_id, self.thread_id, "cannot access local cell from another thread");
+        // Create a mutable slot to call the scope from a reusable closure
+        let mut scope = Some(scope);
+        self.scope_mut(&mut |value: &mut T| {
+            let scope = scope.take().expect("missing scope function");
+            scope(value)
+        })
+    }
+
+    /// Provides scoped access to the underlying value via a reusable scope
+    ///
+    /// # Panic
+    /// This function will panic if called from another thread, or if the cell is already locked
+    ///
+    /// # Warning
+    /// If the runtime checks are disabled via `--cfg embedded_threadsafe_unsafe_disable_runtime_checks`, accessing
+    /// the cell from another thread is undefined behavior.
+    pub fn scope_mut<F, FR>(&self, scope: &mut F) -> FR
+    where
+        F: FnMut(&mut T) -> FR,
+    {
+        let error = match self.try_scope_mut(scope) {
+            Ok(result) => return result,
+            Err(error) => error,
+        };
 
-        // Create mutable slots to transfer state to/from the closure and create the caller
+        // Raise a panic that describes the cell and the failure
+        let type_name = any::type_name::<T>();
+        match error {
+            AccessError::WrongThread { expected, actual } => {
+                panic!(
+                    "cannot access InterruptCell<{type_name}> at {self:p} from thread {actual} (expected {expected})"
+                )
+            }
+            _ => panic!("cannot access InterruptCell<{type_name}> at {self:p} which is already locked"),
+        }
+    }
+
+    /// Provides scoped access to the underlying value if it is accessible from the current context
+    ///
+    /// # Warning
+    /// If the runtime checks are disabled via `--cfg embedded_threadsafe_unsafe_disable_runtime_checks`, accessing
+    /// the cell from another thread is undefined behavior.
+    pub fn try_scope<F, FR>(&self, scope: F) -> Result<FR, AccessError>
+    where
+        F: FnOnce(&mut T) -> FR,
+    {
+        // Create a mutable slot to call the scope from a reusable closure
         let mut scope = Some(scope);
-        let mut result: Option<FR> = None;
-        let mut call_scope = || {
-            // Consume and call the scope
+        self.try_scope_mut(&mut |value: &mut T| {
             let scope = scope.take().expect("missing scope function");
-            let result_ = unsafe { self.raw(scope) };
-            result = Some(result_);
+            scope(value)
+        })
+    }
+
+    /// Ensures that the cell is accessed from the associated thread
+    #[cfg(not(embedded_threadsafe_unsafe_disable_runtime_checks))]
+    fn check_thread(&self) -> Result<(), AccessError> {
+        let thread_id = unsafe { runtime::_runtime_threadid_ZhZIZBv4() };
+        if thread_id != self.thread_id {
+            return Err(AccessError::WrongThread { expected: self.thread_id, actual: thread_id });
+        }
+        Ok(())
+    }
+
+    /// Skips the thread check if the runtime checks are disabled
+    #[cfg(embedded_threadsafe_unsafe_disable_runtime_checks)]
+    fn check_thread(&self) -> Result<(), AccessError> {
+        Ok(())
+    }
+
+    /// Provides scoped access to the underlying value via a reusable scope if it is accessible from the current context
+    fn try_scope_mut<F, FR>(&self, scope: &mut F) -> Result<FR, AccessError>
+    where
+        F: FnMut(&mut T) -> FR,
+    {
+        // Ensure that we access this from the correct thread ID
+        self.check_thread()?;
+
+        // Create a mutable slot to transfer the result from the closure and create the caller
+        let mut result: Option<Result<FR, AccessError>> = None;
+        let mut call_scope = || {
+            // Ensure that the cell is not accessed re-entrantly (e.g. from within its own scope)
+            let Some(_lock) = LockFlag::acquire(&self.locked) else {
+                result = Some(Err(AccessError::Reentrant));
+                return;
+            };
+
+            // Trace the access in debug builds
+            #[cfg(debug_assertions)]
+            let _trace = ScopeTrace::enter(self);
+
+            // Call the scope
+            let result_ = unsafe { self.raw(&mut *scope) };
+            result = Some(Ok(result_));
         };
 
         // Run the implementation in a threadsafe context and return the result
@@ -71,7 +199,21 @@ impl<T> InterruptCell<T> {
         scope(value)
     }
 }
-impl<T> InterruptCell<LazyCell<T>> {
+impl<T, I> InterruptCell<LazyCell<T, I>>
+where
+    I: Initializer<T>,
+{
+    /// Creates a new thread-local cell that is bound to the thread with the ID `thread_id` and lazily initializes its
+    /// value with `init`
+    pub const fn new_lazy_with_threadid(init: I, thread_id: usize) -> Self {
+        Self::new_with_threadid(LazyCell::new(init), thread_id)
+    }
+
+    /// Creates a new thread-local cell that is bound to the current thread and lazily initializes its value with `init`
+    pub fn new_lazy(init: I) -> Self {
+        Self::new(LazyCell::new(init))
+    }
+
     /// Provides scoped access to the underlying lazy cell
     ///
     /// # Panic
@@ -82,10 +224,132 @@ impl<T> InterruptCell<LazyCell<T>> {
     {
         self.scope(|lazy| lazy.scope_mut(scope))
     }
+
+    /// Runs the initializer if the value has not been initialized yet
+    ///
+    /// # Panic
+    /// This function will panic if called from another thread, or if the cell is already locked
+    pub fn initialize(&self) {
+        self.lazy_scope(|_| ())
+    }
+
+    /// Provides scoped access to the underlying value if it has already been initialized, without running the
+    /// initializer otherwise
+    ///
+    /// # Note
+    /// This is useful for teardown code that must not initialize the value just to clean it up.
+    ///
+    /// # Panic
+    /// This function will panic if called from another thread, or if the cell is already locked
+    pub fn lazy_scope_if_initialized<F, FR>(&self, scope: F) -> Option<FR>
+    where
+        F: FnOnce(&mut T) -> FR,
+    {
+        // The scope gives us exclusive access to the lazy cell
+        self.scope(|lazy| unsafe { lazy.scope_if_initialized(scope) })
+    }
+
+    /// Provides scoped access to the underlying value if it is accessible from the current context and has already been
+    /// initialized, without running the initializer otherwise
+    ///
+    /// # Note
+    /// Unlike [`Self::lazy_scope_if_initialized`], this function reports an uninitialized value as
+    /// [`AccessError::NotInitialized`], so it composes with the other access errors via `?`.
+    pub fn try_lazy_scope_if_initialized<F, FR>(&self, scope: F) -> Result<FR, AccessError>
+    where
+        F: FnOnce(&mut T) -> FR,
+    {
+        // The scope gives us exclusive access to the lazy cell
+        let result = self.try_scope(|lazy| unsafe { lazy.scope_if_initialized(scope) })?;
+        result.ok_or(AccessError::NotInitialized)
+    }
+
+    /// Tests whether the value has already been initialized, without running the initializer otherwise
+    ///
+    /// # Panic
+    /// This function will panic if called from another thread, or if the cell is already locked
+    pub fn is_initialized(&self) -> bool {
+        // The scope gives us exclusive access to the lazy cell
+        self.scope(|lazy| unsafe { lazy.is_initialized() })
+    }
+}
+impl<T, const N: usize> InterruptCell<[T; N]> {
+    /// Provides scoped access to the array element at `index`
+    ///
+    /// # Panic
+    /// This function will panic if `index` is out of bounds, or if called from another thread, or if the cell is already
+    /// locked
+    pub fn scope_index<F, FR>(&self, index: usize, scope: F) -> FR
+    where
+        F: FnOnce(&mut T) -> FR,
+    {
+        // Ensure that the index is valid before we enter the scope
+        assert!(index < N, "index out of bounds: the len is {N} but the index is {index}");
+        self.scope(|array| scope(&mut array[index]))
+    }
+
+    /// Calls `scope` for each array element together with its index, all within a single scope
+    ///
+    /// # Panic
+    /// This function will panic if called from another thread, or if the cell is already locked
+    pub fn scope_enumerate<F>(&self, mut scope: F)
+    where
+        F: FnMut(usize, &mut T),
+    {
+        self.scope(|array| array.iter_mut().enumerate().for_each(|(index, element)| scope(index, element)))
+    }
+}
+impl<T> Default for InterruptCell<T>
+where
+    T: Default,
+{
+    fn default() -> Self {
+        Self::new(T::default())
+    }
+}
+impl<T> From<T> for InterruptCell<T> {
+    fn from(value: T) -> Self {
+        Self::new(value)
+    }
+}
+impl<T> PartialEq for InterruptCell<T>
+where
+    T: ?Sized + PartialEq,
+{
+    fn eq(&self, other: &Self) -> bool {
+        // Ensure that we don't lock the same cell twice; compare the value with itself to honor non-reflexive types
+        if ptr::eq(self, other) {
+            #[allow(clippy::eq_op)]
+            return self.scope(|value| *value == *value);
+        }
+
+        // Compare the values
+        self.scope(|value| other.scope(|other| *value == *other))
+    }
+}
+impl<T> Eq for InterruptCell<T>
+where
+    T: ?Sized + Eq,
+{
+    // Marker trait, no members to implement
+}
+impl<T> Hash for InterruptCell<T>
+where
+    T: ?Sized + Hash,
+{
+    fn hash<H>(&self, state: &mut H)
+    where
+        H: Hasher,
+    {
+        // Hash the value as `Some(value)`, or `None` as sentinel if the value is not accessible from the current context
+        if self.try_scope(|value| Some(&*value).hash(state)).is_err() {
+            None::<&T>.hash(state);
+        }
+    }
 }
 impl<T> Debug for InterruptCell<T>
 where
-    T: Debug,
+    T: ?Sized + Debug,
 {
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
         // Return an opaque description if we are in a different thread context
@@ -94,13 +358,90 @@ where
             return f.debug_tuple("InterruptCell").field(&"<opaque due to different thread>").finish();
         }
 
-        // Debug the value
-        self.scope(|value| value.fmt(f))
+        // Debug the value or return an opaque description if the cell is currently locked
+        match self.try_scope(|value| value.fmt(f)) {
+            Ok(result) => result,
+            Err(_) => f.debug_tuple("InterruptCell").field(&"<opaque due to active lock>").finish(),
+        }
+    }
+}
+impl<T> Display for InterruptCell<T>
+where
+    T: ?Sized + Display,
+{
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        // Return an opaque description if we are in a different thread context or if the cell is currently locked
+        match self.try_scope(|value| value.fmt(f)) {
+            Ok(result) => result,
+            Err(AccessError::WrongThread { .. }) => f.write_str("<opaque due to different thread>"),
+            Err(_) => f.write_str("<opaque due to active lock>"),
+        }
+    }
+}
+#[cfg(feature = "defmt")]
+impl<T> defmt::Format for InterruptCell<T>
+where
+    T: ?Sized + defmt::Format,
+{
+    fn format(&self, f: defmt::Formatter) {
+        // Return an opaque description if we are in a different thread context
+        let thread_id = unsafe { runtime::_runtime_threadid_ZhZIZBv4() };
+        if thread_id != self.thread_id {
+            return defmt::write!(f, "InterruptCell(<opaque due to different thread>)");
+        }
+
+        // Format the value or return an opaque description if the cell is currently locked
+        if self.try_scope(|value| value.format(f)).is_err() {
+            defmt::write!(f, "InterruptCell(<opaque due to active lock>)");
+        }
+    }
+}
+#[cfg(feature = "serde")]
+impl<T> serde::Serialize for InterruptCell<T>
+where
+    T: ?Sized + serde::Serialize,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        // Serialize the value or fail if it is not accessible from the current context
+        match self.try_scope(|value| value.serialize(serializer)) {
+            Ok(result) => result,
+            Err(error) => Err(serde::ser::Error::custom(error)),
+        }
+    }
+}
+impl<T> Scope<T> for InterruptCell<T>
+where
+    T: ?Sized,
+{
+    fn scope<F, FR>(&self, scope: F) -> FR
+    where
+        F: FnOnce(&mut T) -> FR,
+    {
+        Self::scope(self, scope)
+    }
+
+    unsafe fn as_ref_unchecked(&self) -> &T {
+        let inner_ptr = self.inner.get();
+        inner_ptr.as_ref().expect("unexpected NULL pointer inside cell")
+    }
+}
+impl<T> TryScope<T> for InterruptCell<T>
+where
+    T: ?Sized,
+{
+    fn try_scope<F, FR>(&self, scope: F) -> Result<FR, AccessError>
+    where
+        F: FnOnce(&mut T) -> FR,
+    {
+        Self::try_scope(self, scope)
     }
 }
 unsafe impl<T> Sync for InterruptCell<T>
 where
-    T: Send,
+    T: ?Sized + Send,
 {
     // Marker trait, no members to implement
 }
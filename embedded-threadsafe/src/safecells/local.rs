@@ -1,52 +1,209 @@
 //! A fast, thread-local cell
 
-use crate::{runtime, LazyCell};
+#[cfg(debug_assertions)]
+use crate::tracing::ScopeTrace;
+use crate::{
+    error::AccessError,
+    lazy::Initializer,
+    runtime,
+    traits::{Scope, TryScope},
+    LazyCell,
+};
 use core::{
-    cell::UnsafeCell,
-    fmt::{self, Debug, Formatter},
+    any,
+    cell::{Cell, UnsafeCell},
+    fmt::{self, Debug, Display, Formatter},
+    hash::{Hash, Hasher},
+    marker::PhantomData,
+    ops::{Deref, DerefMut},
+    ptr,
 };
 
 /// A fast, thread-local cell
 ///
 /// # Warning
 /// This cell must not be accessed from another thread or an interrupt context; doing so will raise a panic.
-pub struct LocalCell<T> {
-    /// The wrapped value
-    inner: UnsafeCell<T>,
+/// If the crate is built with `--cfg embedded_threadsafe_unsafe_disable_runtime_checks` (e.g. via `RUSTFLAGS`), the
+/// checks are skipped and such an access is undefined behavior.
+///
+/// # Note
+/// The value may be unsized, so a `&LocalCell<W>` coerces to e.g. a `&LocalCell<dyn Write>` for any `W: Write`.
+///
+/// # Example
+/// ```rust
+/// use core::fmt::Write;
+/// use embedded_threadsafe::LocalCell;
+///
+/// // Log into any writer without knowing its concrete type
+/// fn log(writer: &LocalCell<dyn Write>, message: &str) {
+///     writer.scope(|writer| writer.write_str(message)).expect("failed to write log message");
+/// }
+/// ```
+pub struct LocalCell<T>
+where
+    T: ?Sized,
+{
     /// The associated thread ID
     thread_id: usize,
+    /// Whether the cell is currently locked
+    locked: Cell<bool>,
+    /// The wrapped value
+    inner: UnsafeCell<T>,
 }
 impl<T> LocalCell<T> {
-    /// Creates a new thread-local cell
+    /// Creates a new thread-local cell that is bound to the thread with the ID `thread_id`
+    ///
+    /// # Note
+    /// This is the `const` constructor, so it can be used to initialize a `static`.
     pub const fn new_with_threadid(value: T, thread_id: usize) -> Self {
-        Self { inner: UnsafeCell::new(value), thread_id }
+        Self { thread_id, locked: Cell::new(false), inner: UnsafeCell::new(value) }
     }
 
-    /// Creates a new thread-local cell
+    /// Creates a new thread-local cell that is bound to the current thread
+    ///
+    /// # Note
+    /// The thread ID is queried at runtime, so this constructor cannot be used to initialize a `static`; use
+    /// [`Self::new_with_threadid`] instead.
     pub fn new(value: T) -> Self {
         // Get the thread ID and init self
         let thread_id = unsafe { runtime::_runtime_threadid_ZhZIZBv4() };
         Self::new_with_threadid(value, thread_id)
     }
 
+    /// Rebinds the cell to the thread with the ID `new_thread_id` if it is owned by the current thread, or returns the
+    /// cell unchanged otherwise
+    ///
+    /// # Note
+    /// As the cell is passed by value, there cannot be any other references to it, so no critical section is required.
+    pub fn try_migrate(self, new_thread_id: usize) -> Result<Self, Self> {
+        // Ensure that we are the owner of the cell
+        let thread_id = unsafe { runtime::_runtime_threadid_ZhZIZBv4() };
+        if thread_id != self.thread_id {
+            return Err(self);
+        }
+        Ok(Self { thread_id: new_thread_id, ..self })
+    }
+}
+impl<T> LocalCell<T>
+where
+    T: ?Sized,
+{
     /// Provides scoped access to the underlying value
     ///
     /// # Panic
-    /// This function will panic if called from another thread or interrupt context
+    /// This function will panic if called from another thread or interrupt context, or if the cell is already locked
+    ///
+    /// # Warning
+    /// If the runtime checks are disabled via `--cfg embedded_threadsafe_unsafe_disable_runtime_checks`, accessing
+    /// the cell from another thread or an interrupt context is undefined behavior.
     pub fn scope<F, FR>(&self, scope: F) -> FR
     where
         F: FnOnce(&mut T) -> FR,
     {
+        // Lock the cell and provide access to the value
+        let mut guard = self.lock();
+        scope(&mut guard)
+    }
+
+    /// Provides scoped access to the underlying value via a reusable scope
+    ///
+    /// # Panic
+    /// This function will panic if called from another thread or interrupt context, or if the cell is already locked
+    ///
+    /// # Warning
+    /// If the runtime checks are disabled via `--cfg embedded_threadsafe_unsafe_disable_runtime_checks`, accessing
+    /// the cell from another thread or an interrupt context is undefined behavior.
+    pub fn scope_mut<F, FR>(&self, scope: &mut F) -> FR
+    where
+        F: FnMut(&mut T) -> FR,
+    {
+        // Lock the cell and provide access to the value
+        let mut guard = self.lock();
+        scope(&mut guard)
+    }
+
+    /// Locks the cell and returns a guard that provides access to the underlying value until it is dropped
+    ///
+    /// # Panic
+    /// This function will panic if called from another thread or interrupt context, or if the cell is already locked
+    ///
+    /// # Warning
+    /// If the runtime checks are disabled via `--cfg embedded_threadsafe_unsafe_disable_runtime_checks`, accessing
+    /// the cell from another thread or an interrupt context is undefined behavior.
+    pub fn lock(&self) -> LocalCellGuard<'_, T> {
+        let error = match self.try_lock() {
+            Ok(guard) => return guard,
+            Err(error) => error,
+        };
+
+        // Raise a panic that describes the cell and the failure
+        let type_name = any::type_name::<T>();
+        match error {
+            AccessError::InterruptContext => {
+                panic!("cannot access LocalCell<{type_name}> at {self:p} from an interrupt handler")
+            }
+            AccessError::WrongThread { expected, actual } => {
+                panic!("cannot access LocalCell<{type_name}> at {self:p} from thread {actual} (expected {expected})")
+            }
+            _ => panic!("cannot access LocalCell<{type_name}> at {self:p} which is already locked"),
+        }
+    }
+
+    /// Locks the cell if it is accessible from the current context and returns a guard that provides access to the
+    /// underlying value until it is dropped
+    ///
+    /// # Warning
+    /// If the runtime checks are disabled via `--cfg embedded_threadsafe_unsafe_disable_runtime_checks`, accessing
+    /// the cell from another thread or an interrupt context is undefined behavior.
+    pub fn try_lock(&self) -> Result<LocalCellGuard<'_, T>, AccessError> {
+        // Ensure that we access this from the correct context and that the cell is not locked already
+        self.check_context()?;
+        let was_locked = self.locked.replace(true);
+        if was_locked {
+            return Err(AccessError::Reentrant);
+        }
+        Ok(LocalCellGuard {
+            cell: self,
+            #[cfg(debug_assertions)]
+            trace: Some(ScopeTrace::enter(self)),
+            _thread_bound: PhantomData,
+        })
+    }
+
+    /// Ensures that the cell is accessed from the associated thread and not from an interrupt handler
+    #[cfg(not(embedded_threadsafe_unsafe_disable_runtime_checks))]
+    fn check_context(&self) -> Result<(), AccessError> {
         // Ensure that we are not in an interrupt handler
         let is_interrupted = unsafe { runtime::_runtime_isinterrupted_v5tnnoC7() };
-        assert!(!is_interrupted, "cannot access local cell from an interrupt handler");
+        if is_interrupted {
+            return Err(AccessError::InterruptContext);
+        }
 
         // Ensure that we access this from the correct thread
         let thread_id = unsafe { runtime::_runtime_threadid_ZhZIZBv4() };
-        assert_eq!(thread_id, self.thread_id, "cannot access local cell from another thread");
+        if thread_id != self.thread_id {
+            return Err(AccessError::WrongThread { expected: self.thread_id, actual: thread_id });
+        }
+        Ok(())
+    }
 
-        // Provide access to the value
-        unsafe { self.raw(scope) }
+    /// Skips the context checks if the runtime checks are disabled
+    #[cfg(embedded_threadsafe_unsafe_disable_runtime_checks)]
+    fn check_context(&self) -> Result<(), AccessError> {
+        Ok(())
+    }
+
+    /// Provides scoped access to the underlying value if it is accessible from the current context
+    ///
+    /// # Warning
+    /// If the runtime checks are disabled via `--cfg embedded_threadsafe_unsafe_disable_runtime_checks`, accessing
+    /// the cell from another thread or an interrupt context is undefined behavior.
+    pub fn try_scope<F, FR>(&self, scope: F) -> Result<FR, AccessError>
+    where
+        F: FnOnce(&mut T) -> FR,
+    {
+        let mut guard = self.try_lock()?;
+        Ok(scope(&mut guard))
     }
 
     /// Provides an unsafe raw scoped access to the underlying value
@@ -64,7 +221,21 @@ impl<T> LocalCell<T> {
         scope(value)
     }
 }
-impl<T> LocalCell<LazyCell<T>> {
+impl<T, I> LocalCell<LazyCell<T, I>>
+where
+    I: Initializer<T>,
+{
+    /// Creates a new thread-local cell that is bound to the thread with the ID `thread_id` and lazily initializes its
+    /// value with `init`
+    pub const fn new_lazy_with_threadid(init: I, thread_id: usize) -> Self {
+        Self::new_with_threadid(LazyCell::new(init), thread_id)
+    }
+
+    /// Creates a new thread-local cell that is bound to the current thread and lazily initializes its value with `init`
+    pub fn new_lazy(init: I) -> Self {
+        Self::new(LazyCell::new(init))
+    }
+
     /// Provides scoped access to the underlying lazy cell
     ///
     /// # Panic
@@ -75,10 +246,132 @@ impl<T> LocalCell<LazyCell<T>> {
     {
         self.scope(|lazy| lazy.scope_mut(scope))
     }
+
+    /// Runs the initializer if the value has not been initialized yet
+    ///
+    /// # Panic
+    /// This function will panic if called from another thread or interrupt context, or if the cell is already locked
+    pub fn initialize(&self) {
+        self.lazy_scope(|_| ())
+    }
+
+    /// Provides scoped access to the underlying value if it has already been initialized, without running the
+    /// initializer otherwise
+    ///
+    /// # Note
+    /// This is useful for teardown code that must not initialize the value just to clean it up.
+    ///
+    /// # Panic
+    /// This function will panic if called from another thread or interrupt context, or if the cell is already locked
+    pub fn lazy_scope_if_initialized<F, FR>(&self, scope: F) -> Option<FR>
+    where
+        F: FnOnce(&mut T) -> FR,
+    {
+        // The scope gives us exclusive access to the lazy cell
+        self.scope(|lazy| unsafe { lazy.scope_if_initialized(scope) })
+    }
+
+    /// Provides scoped access to the underlying value if it is accessible from the current context and has already been
+    /// initialized, without running the initializer otherwise
+    ///
+    /// # Note
+    /// Unlike [`Self::lazy_scope_if_initialized`], this function reports an uninitialized value as
+    /// [`AccessError::NotInitialized`], so it composes with the other access errors via `?`.
+    pub fn try_lazy_scope_if_initialized<F, FR>(&self, scope: F) -> Result<FR, AccessError>
+    where
+        F: FnOnce(&mut T) -> FR,
+    {
+        // The scope gives us exclusive access to the lazy cell
+        let result = self.try_scope(|lazy| unsafe { lazy.scope_if_initialized(scope) })?;
+        result.ok_or(AccessError::NotInitialized)
+    }
+
+    /// Tests whether the value has already been initialized, without running the initializer otherwise
+    ///
+    /// # Panic
+    /// This function will panic if called from another thread or interrupt context, or if the cell is already locked
+    pub fn is_initialized(&self) -> bool {
+        // The scope gives us exclusive access to the lazy cell
+        self.scope(|lazy| unsafe { lazy.is_initialized() })
+    }
+}
+impl<T, const N: usize> LocalCell<[T; N]> {
+    /// Provides scoped access to the array element at `index`
+    ///
+    /// # Panic
+    /// This function will panic if `index` is out of bounds, or if called from another thread or interrupt context, or
+    /// if the cell is already locked
+    pub fn scope_index<F, FR>(&self, index: usize, scope: F) -> FR
+    where
+        F: FnOnce(&mut T) -> FR,
+    {
+        // Ensure that the index is valid before we enter the scope
+        assert!(index < N, "index out of bounds: the len is {N} but the index is {index}");
+        self.scope(|array| scope(&mut array[index]))
+    }
+
+    /// Calls `scope` for each array element together with its index, all within a single scope
+    ///
+    /// # Panic
+    /// This function will panic if called from another thread or interrupt context, or if the cell is already locked
+    pub fn scope_enumerate<F>(&self, mut scope: F)
+    where
+        F: FnMut(usize, &mut T),
+    {
+        self.scope(|array| array.iter_mut().enumerate().for_each(|(index, element)| scope(index, element)))
+    }
+}
+impl<T> Default for LocalCell<T>
+where
+    T: Default,
+{
+    fn default() -> Self {
+        Self::new(T::default())
+    }
+}
+impl<T> From<T> for LocalCell<T> {
+    fn from(value: T) -> Self {
+        Self::new(value)
+    }
+}
+impl<T> PartialEq for LocalCell<T>
+where
+    T: ?Sized + PartialEq,
+{
+    fn eq(&self, other: &Self) -> bool {
+        // Ensure that we don't lock the same cell twice; compare the value with itself to honor non-reflexive types
+        if ptr::eq(self, other) {
+            #[allow(clippy::eq_op)]
+            return self.scope(|value| *value == *value);
+        }
+
+        // Compare the values
+        self.scope(|value| other.scope(|other| *value == *other))
+    }
+}
+impl<T> Eq for LocalCell<T>
+where
+    T: ?Sized + Eq,
+{
+    // Marker trait, no members to implement
+}
+impl<T> Hash for LocalCell<T>
+where
+    T: ?Sized + Hash,
+{
+    fn hash<H>(&self, state: &mut H)
+    where
+        H: Hasher,
+    {
+        // Hash the value as `Some(value)`, or `None` as sentinel if the value is not accessible from the current context
+        if self.try_scope(|value| Some(&*value).hash(state)).is_err() {
+            None::<&T>.hash(state);
+        }
+    }
 }
 impl<T> Debug for LocalCell<T>
 where
-    T: Debug,
+    T: ?Sized + Debug,
 {
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
         // Return an opaque description if we are in a different thread
@@ -93,13 +386,157 @@ where
             return f.debug_tuple("LocalCell").field(&"<opaque due to interrupt context>").finish();
         }
 
+        // Return an opaque description if the cell is currently locked
+        if self.locked.get() {
+            return f.debug_tuple("LocalCell").field(&"<opaque due to active lock>").finish();
+        }
+
         // Debug the value
         self.scope(|value| value.fmt(f))
     }
 }
+impl<T> Display for LocalCell<T>
+where
+    T: ?Sized + Display,
+{
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        // Return an opaque description if the value is not accessible from the current context
+        match self.try_scope(|value| value.fmt(f)) {
+            Ok(result) => result,
+            Err(AccessError::WrongThread { .. }) => f.write_str("<opaque due to different thread>"),
+            Err(AccessError::InterruptContext) => f.write_str("<opaque due to interrupt context>"),
+            Err(_) => f.write_str("<opaque due to active lock>"),
+        }
+    }
+}
+#[cfg(feature = "defmt")]
+impl<T> defmt::Format for LocalCell<T>
+where
+    T: ?Sized + defmt::Format,
+{
+    fn format(&self, f: defmt::Formatter) {
+        // Return an opaque description if we are in a different thread
+        let thread_id = unsafe { runtime::_runtime_threadid_ZhZIZBv4() };
+        if thread_id != self.thread_id {
+            return defmt::write!(f, "LocalCell(<opaque due to different thread>)");
+        }
+
+        // Return an opaque description if we are in an interrupt context
+        let is_interrupted = unsafe { runtime::_runtime_isinterrupted_v5tnnoC7() };
+        if is_interrupted {
+            return defmt::write!(f, "LocalCell(<opaque due to interrupt context>)");
+        }
+
+        // Return an opaque description if the cell is currently locked
+        if self.locked.get() {
+            return defmt::write!(f, "LocalCell(<opaque due to active lock>)");
+        }
+
+        // Format the value
+        self.scope(|value| value.format(f))
+    }
+}
+#[cfg(feature = "serde")]
+impl<T> serde::Serialize for LocalCell<T>
+where
+    T: ?Sized + serde::Serialize,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        // Serialize the value or fail if it is not accessible from the current context
+        match self.try_scope(|value| value.serialize(serializer)) {
+            Ok(result) => result,
+            Err(error) => Err(serde::ser::Error::custom(error)),
+        }
+    }
+}
+impl<T> Scope<T> for LocalCell<T>
+where
+    T: ?Sized,
+{
+    fn scope<F, FR>(&self, scope: F) -> FR
+    where
+        F: FnOnce(&mut T) -> FR,
+    {
+        Self::scope(self, scope)
+    }
+
+    unsafe fn as_ref_unchecked(&self) -> &T {
+        let inner_ptr = self.inner.get();
+        inner_ptr.as_ref().expect("unexpected NULL pointer inside cell")
+    }
+}
+impl<T> TryScope<T> for LocalCell<T>
+where
+    T: ?Sized,
+{
+    fn try_scope<F, FR>(&self, scope: F) -> Result<FR, AccessError>
+    where
+        F: FnOnce(&mut T) -> FR,
+    {
+        Self::try_scope(self, scope)
+    }
+}
 unsafe impl<T> Sync for LocalCell<T>
 where
-    T: Send,
+    T: ?Sized + Send,
 {
     // Marker trait, no members to implement
 }
+
+/// A guard that provides access to the value of a locked [`LocalCell`] and unlocks the cell on drop
+pub struct LocalCellGuard<'a, T>
+where
+    T: ?Sized,
+{
+    /// The locked cell
+    cell: &'a LocalCell<T>,
+    /// Traces the access in debug builds; this is taken on drop to fire the exit event before the cell is unlocked
+    #[cfg(debug_assertions)]
+    trace: Option<ScopeTrace>,
+    /// Binds the guard to the locking thread
+    _thread_bound: PhantomData<*mut T>,
+}
+impl<T> Deref for LocalCellGuard<'_, T>
+where
+    T: ?Sized,
+{
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        // The value is exclusively ours until the guard is dropped
+        let inner_ptr = self.cell.inner.get();
+        unsafe { inner_ptr.as_ref() }.expect("unexpected NULL pointer inside cell")
+    }
+}
+impl<T> DerefMut for LocalCellGuard<'_, T>
+where
+    T: ?Sized,
+{
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        // The value is exclusively ours until the guard is dropped
+        let inner_ptr = self.cell.inner.get();
+        unsafe { inner_ptr.as_mut() }.expect("unexpected NULL pointer inside cell")
+    }
+}
+impl<T> Debug for LocalCellGuard<'_, T>
+where
+    T: ?Sized + Debug,
+{
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        T::fmt(self, f)
+    }
+}
+impl<T> Drop for LocalCellGuard<'_, T>
+where
+    T: ?Sized,
+{
+    fn drop(&mut self) {
+        // Finish the trace while the cell is still locked
+        #[cfg(debug_assertions)]
+        drop(self.trace.take());
+        self.cell.locked.set(false);
+    }
+}
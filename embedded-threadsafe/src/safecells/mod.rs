@@ -3,3 +3,26 @@
 pub mod interrupt;
 pub mod local;
 pub mod shared;
+
+use core::cell::Cell;
+
+/// Holds the lock flag of a cell and releases it on drop, even if the scope panics
+pub(crate) struct LockFlag<'a> {
+    /// The lock flag of the cell
+    locked: &'a Cell<bool>,
+}
+impl<'a> LockFlag<'a> {
+    /// Sets the lock flag, or returns `None` if the cell is already locked
+    pub fn acquire(locked: &'a Cell<bool>) -> Option<Self> {
+        let was_locked = locked.replace(true);
+        if was_locked {
+            return None;
+        }
+        Some(Self { locked })
+    }
+}
+impl Drop for LockFlag<'_> {
+    fn drop(&mut self) {
+        self.locked.set(false);
+    }
+}
@@ -1,35 +1,132 @@
 //! A cell that can be safely be shared across thread boundaries and interrupt contexts
 
-use crate::{runtime, LazyCell};
+#[cfg(debug_assertions)]
+use crate::tracing::ScopeTrace;
+use crate::{
+    error::AccessError,
+    lazy::Initializer,
+    runtime,
+    safecells::LockFlag,
+    token::CriticalSectionToken,
+    traits::{Scope, TryScope},
+    volatile::VolatileRef,
+    LazyCell,
+};
 use core::{
-    cell::UnsafeCell,
-    fmt::{self, Debug, Formatter},
+    cell::{Cell, UnsafeCell},
+    fmt::{self, Debug, Display, Formatter},
+    hash::{Hash, Hasher},
+    mem,
+    ops::{BitAnd, BitAndAssign, BitOrAssign, BitXorAssign, Not},
+    ptr,
 };
 
 /// A cell that can be safely be shared across thread boundaries and interrupt contexts
-pub struct SharedCell<T> {
+pub struct SharedCell<T>
+where
+    T: ?Sized,
+{
+    /// Whether the cell is currently locked
+    locked: Cell<bool>,
+    /// Whether a scope has panicked while accessing the value
+    poisoned: Cell<bool>,
     /// The wrapped value
     inner: UnsafeCell<T>,
 }
 impl<T> SharedCell<T> {
     /// Creates a new cell
     pub const fn new(value: T) -> Self {
-        Self { inner: UnsafeCell::new(value) }
+        Self { locked: Cell::new(false), poisoned: Cell::new(false), inner: UnsafeCell::new(value) }
     }
 
+    /// Provides scoped access to the underlying value via a reference that only allows volatile reads and writes
+    ///
+    /// # Panic
+    /// This function will panic if the cell is already locked or has been poisoned by a panicking scope
+    pub fn volatile_scope<F, FR>(&self, scope: F) -> FR
+    where
+        F: FnOnce(VolatileRef<'_, T>) -> FR,
+    {
+        self.scope(|value| scope(VolatileRef::new(value)))
+    }
+}
+impl<T> SharedCell<T>
+where
+    T: ?Sized,
+{
     /// Provides scoped access to the underlying value
+    ///
+    /// # Panic
+    /// This function will panic if the cell is already locked or has been poisoned by a panicking scope
     pub fn scope<F, FR>(&self, scope: F) -> FR
     where
         F: FnOnce(&mut T) -> FR,
     {
-        // Create mutable slots to transfer state to/from the closure and create the caller
+        // Create a mutable slot to call the scope from a reusable closure
         let mut scope = Some(scope);
-        let mut result: Option<FR> = None;
-        let mut call_scope = || {
-            // Consume and call the scope
+        self.scope_mut(&mut |value: &mut T| {
+            let scope = scope.take().expect("missing scope function");
+            scope(value)
+        })
+    }
+
+    /// Provides scoped access to the underlying value via a reusable scope
+    ///
+    /// # Panic
+    /// This function will panic if the cell is already locked or has been poisoned by a panicking scope
+    pub fn scope_mut<F, FR>(&self, scope: &mut F) -> FR
+    where
+        F: FnMut(&mut T) -> FR,
+    {
+        Self::unwrap_access(self.try_scope_mut(scope))
+    }
+
+    /// Provides scoped access to the underlying value within an already entered critical section
+    ///
+    /// # Note
+    /// Unlike `scope`, this function does not enter a new critical section, so it can be used to update multiple cells
+    /// atomically without nesting critical sections.
+    ///
+    /// # Panic
+    /// This function will panic if the cell is already locked or has been poisoned by a panicking scope
+    pub fn scope_with_token<F, FR>(&self, token: &CriticalSectionToken, scope: F) -> FR
+    where
+        F: FnOnce(&mut T) -> FR,
+    {
+        // Create a mutable slot to call the scope from a reusable closure
+        let mut scope = Some(scope);
+        let result = self.try_scope_with_token_mut(token, &mut |value: &mut T| {
+            let scope = scope.take().expect("missing scope function");
+            scope(value)
+        });
+        Self::unwrap_access(result)
+    }
+
+    /// Provides scoped access to the underlying value if the cell is not already locked and has not been poisoned by a
+    /// panicking scope
+    pub fn try_scope<F, FR>(&self, scope: F) -> Result<FR, AccessError>
+    where
+        F: FnOnce(&mut T) -> FR,
+    {
+        // Create a mutable slot to call the scope from a reusable closure
+        let mut scope = Some(scope);
+        self.try_scope_mut(&mut |value: &mut T| {
             let scope = scope.take().expect("missing scope function");
-            let result_ = unsafe { self.raw(scope) };
-            result = Some(result_);
+            scope(value)
+        })
+    }
+
+    /// Provides scoped access to the underlying value via a reusable scope if the cell is not already locked and has
+    /// not been poisoned by a panicking scope
+    fn try_scope_mut<F, FR>(&self, scope: &mut F) -> Result<FR, AccessError>
+    where
+        F: FnMut(&mut T) -> FR,
+    {
+        // Create a mutable slot to transfer the result from the closure and create the caller
+        let mut result: Option<Result<FR, AccessError>> = None;
+        let mut call_scope = || {
+            let token = unsafe { CriticalSectionToken::new() };
+            result = Some(self.try_scope_with_token_mut(&token, scope));
         };
 
         // Run the implementation in a threadsafe context and return the result
@@ -37,6 +134,137 @@ impl<T> SharedCell<T> {
         result.expect("implementation scope did not set result value")
     }
 
+    /// Provides scoped access to the underlying value via a reusable scope within an already entered critical section
+    /// if the cell is not already locked and has not been poisoned by a panicking scope
+    fn try_scope_with_token_mut<F, FR>(&self, _token: &CriticalSectionToken, scope: &mut F) -> Result<FR, AccessError>
+    where
+        F: FnMut(&mut T) -> FR,
+    {
+        // Ensure that the cell is not accessed re-entrantly (e.g. from within its own scope)
+        let Some(_lock) = LockFlag::acquire(&self.locked) else {
+            return Err(AccessError::Reentrant);
+        };
+
+        // Ensure that the value has not been left in an inconsistent state
+        if self.poisoned.get() {
+            return Err(AccessError::Poisoned);
+        }
+
+        // Trace the access in debug builds
+        #[cfg(debug_assertions)]
+        let _trace = ScopeTrace::enter(self);
+
+        // Call the scope; the poison flag is only cleared again if the scope returns normally
+        self.poisoned.set(true);
+        let result = unsafe { self.raw(scope) };
+        self.poisoned.set(false);
+        Ok(result)
+    }
+
+    /// Unwraps the result of an access or raises the appropriate panic
+    fn unwrap_access<FR>(result: Result<FR, AccessError>) -> FR {
+        match result {
+            Ok(result) => result,
+            Err(AccessError::Reentrant) => panic!("cannot access shared cell which is already locked"),
+            Err(_) => panic!("cannot access shared cell which has been poisoned by a panic"),
+        }
+    }
+
+    /// Replaces the underlying value with `new_value` if it is equal to `expected`
+    ///
+    /// Following the conventions of `compare_exchange`, the result contains the previous value on success, or a copy of
+    /// the current value on failure.
+    pub fn compare_replace(&self, expected: T, new_value: T) -> Result<T, T>
+    where
+        T: PartialEq + Clone,
+    {
+        self.scope(|value| {
+            if *value != expected {
+                return Err(value.clone());
+            }
+            Ok(mem::replace(value, new_value))
+        })
+    }
+
+    /// Replaces the underlying value with the result of `update` if it returns `Some`
+    ///
+    /// Following the conventions of `fetch_update`, the result contains the previous value on success, or the current
+    /// value if `update` returns `None`. As the update runs within a single scope, it is never retried.
+    ///
+    /// # Panic
+    /// This function will panic if the cell is already locked or has been poisoned by a panicking scope
+    pub fn fetch_update<F>(&self, update: F) -> Result<T, T>
+    where
+        T: Copy,
+        F: FnOnce(T) -> Option<T>,
+    {
+        self.scope(|value| {
+            let previous = *value;
+            let new_value = update(previous).ok_or(previous)?;
+            *value = new_value;
+            Ok(previous)
+        })
+    }
+
+    /// Tests whether the cell has been poisoned by a panicking scope
+    pub fn is_poisoned(&self) -> bool {
+        let mut poisoned = false;
+        unsafe { runtime::_runtime_threadsafe_e0LtH0x3(&mut || poisoned = self.poisoned.get()) };
+        poisoned
+    }
+
+    /// Clears the poison flag so that the value can be accessed again
+    ///
+    /// # Note
+    /// The value may have been left in an inconsistent state by the panicking scope; it is up to the caller to restore
+    /// a consistent state if necessary.
+    pub fn clear_poison(&self) {
+        unsafe { runtime::_runtime_threadsafe_e0LtH0x3(&mut || self.poisoned.set(false)) };
+    }
+
+    /// Provides scoped read-only access to the underlying value within an interrupt-free instead of a threadsafe
+    /// critical section
+    ///
+    /// # Panic
+    /// This function will panic if the cell is already locked or has been poisoned by a panicking scope
+    ///
+    /// # Safety
+    /// An interrupt-free critical section does not protect against accesses from other cores, so this function must
+    /// only be used on single-core targets.
+    pub unsafe fn scope_ref_interrupt_free<F, FR>(&self, scope: F) -> FR
+    where
+        F: FnOnce(&T) -> FR,
+    {
+        // Create mutable slots to transfer state to/from the closure and create the caller
+        let mut scope = Some(scope);
+        let mut result: Option<Result<FR, AccessError>> = None;
+        let mut call_scope = || {
+            // Ensure that the cell is not accessed re-entrantly (e.g. from within its own scope)
+            let Some(_lock) = LockFlag::acquire(&self.locked) else {
+                result = Some(Err(AccessError::Reentrant));
+                return;
+            };
+
+            // Ensure that the value has not been left in an inconsistent state
+            if self.poisoned.get() {
+                result = Some(Err(AccessError::Poisoned));
+                return;
+            }
+
+            // Trace the access in debug builds
+            #[cfg(debug_assertions)]
+            let _trace = ScopeTrace::enter(self);
+
+            // Consume and call the scope; a read-only scope cannot leave the value in an inconsistent state
+            let scope = scope.take().expect("missing scope function");
+            result = Some(Ok(scope(self.as_ref_unchecked())));
+        };
+
+        // Run the implementation in an interrupt-safe context and return the result
+        runtime::_runtime_interruptsafe_1l52Ge5e(&mut call_scope);
+        Self::unwrap_access(result.expect("implementation scope did not set result value"))
+    }
+
     /// Provides an unsafe raw scoped access to the underlying value
     ///
     /// # Safety
@@ -52,7 +280,22 @@ impl<T> SharedCell<T> {
         scope(value)
     }
 }
-impl<T> SharedCell<LazyCell<T>> {
+impl<T> SharedCell<LazyCell<T>>
+where
+    T: Default,
+{
+    /// Creates a new shared cell that lazily initializes its value with `T::default()` on first access
+    ///
+    /// # Note
+    /// This allows to use types with a non-const `Default` implementation in a static.
+    pub const fn with_default() -> Self {
+        Self::new(LazyCell::new(T::default))
+    }
+}
+impl<T, I> SharedCell<LazyCell<T, I>>
+where
+    I: Initializer<T>,
+{
     /// Provides scoped access to the underlying lazy cell
     ///
     /// # Panic
@@ -63,18 +306,338 @@ impl<T> SharedCell<LazyCell<T>> {
     {
         self.scope(|lazy| lazy.scope_mut(scope))
     }
+
+    /// Runs the initializer if the value has not been initialized yet
+    ///
+    /// # Panic
+    /// This function will panic if the cell is already locked or has been poisoned by a panicking scope
+    pub fn initialize(&self) {
+        self.lazy_scope(|_| ())
+    }
+
+    /// Provides scoped access to the underlying value if it has already been initialized, without running the
+    /// initializer otherwise
+    ///
+    /// # Note
+    /// This is useful for teardown code that must not initialize the value just to clean it up.
+    ///
+    /// # Panic
+    /// This function will panic if the cell is already locked or has been poisoned by a panicking scope
+    pub fn lazy_scope_if_initialized<F, FR>(&self, scope: F) -> Option<FR>
+    where
+        F: FnOnce(&mut T) -> FR,
+    {
+        // The scope gives us exclusive access to the lazy cell
+        self.scope(|lazy| unsafe { lazy.scope_if_initialized(scope) })
+    }
+
+    /// Provides scoped access to the underlying value if it is accessible from the current context and has already been
+    /// initialized, without running the initializer otherwise
+    ///
+    /// # Note
+    /// Unlike [`Self::lazy_scope_if_initialized`], this function reports an uninitialized value as
+    /// [`AccessError::NotInitialized`], so it composes with the other access errors via `?`.
+    pub fn try_lazy_scope_if_initialized<F, FR>(&self, scope: F) -> Result<FR, AccessError>
+    where
+        F: FnOnce(&mut T) -> FR,
+    {
+        // The scope gives us exclusive access to the lazy cell
+        let result = self.try_scope(|lazy| unsafe { lazy.scope_if_initialized(scope) })?;
+        result.ok_or(AccessError::NotInitialized)
+    }
+
+    /// Tests whether the value has already been initialized, without running the initializer otherwise
+    ///
+    /// # Panic
+    /// This function will panic if the cell is already locked or has been poisoned by a panicking scope
+    pub fn is_initialized(&self) -> bool {
+        // The scope gives us exclusive access to the lazy cell
+        self.scope(|lazy| unsafe { lazy.is_initialized() })
+    }
+}
+impl<T, const N: usize> SharedCell<[T; N]> {
+    /// Provides scoped access to the array element at `index`
+    ///
+    /// # Panic
+    /// This function will panic if `index` is out of bounds, or if the cell is already locked or has been poisoned by a
+    /// panicking scope
+    pub fn scope_index<F, FR>(&self, index: usize, scope: F) -> FR
+    where
+        F: FnOnce(&mut T) -> FR,
+    {
+        // Ensure that the index is valid before we enter the scope
+        assert!(index < N, "index out of bounds: the len is {N} but the index is {index}");
+        self.scope(|array| scope(&mut array[index]))
+    }
+
+    /// Calls `scope` for each array element together with its index, all within a single scope
+    ///
+    /// # Panic
+    /// This function will panic if the cell is already locked or has been poisoned by a panicking scope
+    pub fn scope_enumerate<F>(&self, mut scope: F)
+    where
+        F: FnMut(usize, &mut T),
+    {
+        self.scope(|array| array.iter_mut().enumerate().for_each(|(index, element)| scope(index, element)))
+    }
+}
+impl<T> SharedCell<T>
+where
+    T: Copy + BitAnd<Output = T> + BitAndAssign + BitOrAssign + BitXorAssign + Not<Output = T>,
+{
+    /// Sets the bits in `mask`
+    ///
+    /// # Panic
+    /// This function will panic if the cell is already locked or has been poisoned by a panicking scope
+    pub fn set_bits(&self, mask: T) {
+        self.scope(|value| *value |= mask)
+    }
+
+    /// Clears the bits in `mask`
+    ///
+    /// # Panic
+    /// This function will panic if the cell is already locked or has been poisoned by a panicking scope
+    pub fn clear_bits(&self, mask: T) {
+        self.scope(|value| *value &= !mask)
+    }
+
+    /// Toggles the bits in `mask`
+    ///
+    /// # Panic
+    /// This function will panic if the cell is already locked or has been poisoned by a panicking scope
+    pub fn toggle_bits(&self, mask: T) {
+        self.scope(|value| *value ^= mask)
+    }
+
+    /// Reads the bits in `mask`, all other bits are zero
+    ///
+    /// # Panic
+    /// This function will panic if the cell is already locked or has been poisoned by a panicking scope
+    pub fn read_bits(&self, mask: T) -> T {
+        self.scope(|value| *value & mask)
+    }
+}
+impl<T> SharedCell<Option<T>> {
+    /// Inserts the value computed by `init` if the cell is empty
+    ///
+    /// # Panic
+    /// This function will panic if the cell is already locked or has been poisoned by a panicking scope
+    pub fn get_or_insert<F>(&self, init: F)
+    where
+        F: FnOnce() -> T,
+    {
+        self.scope(|value| {
+            value.get_or_insert_with(init);
+        })
+    }
+
+    /// Takes the value out of the cell, leaving the cell empty
+    ///
+    /// # Panic
+    /// This function will panic if the cell is already locked or has been poisoned by a panicking scope
+    pub fn take_value(&self) -> Option<T> {
+        self.scope(|value| value.take())
+    }
+
+    /// Puts `new_value` into the cell and returns the previous value if any
+    ///
+    /// # Panic
+    /// This function will panic if the cell is already locked or has been poisoned by a panicking scope
+    pub fn put_value(&self, new_value: T) -> Option<T> {
+        self.scope(|value| value.replace(new_value))
+    }
+}
+#[cfg(feature = "heapless")]
+impl<T, const N: usize> SharedCell<heapless::Deque<T, N>> {
+    /// Appends a value to the back of the queue, or returns `false` if the queue is full
+    ///
+    /// # Panic
+    /// This function will panic if the cell is already locked or has been poisoned by a panicking scope
+    pub fn push_back(&self, value: T) -> bool {
+        self.scope(|queue| queue.push_back(value).is_ok())
+    }
+
+    /// Removes the value from the front of the queue, or returns `None` if the queue is empty
+    ///
+    /// # Panic
+    /// This function will panic if the cell is already locked or has been poisoned by a panicking scope
+    pub fn pop_front(&self) -> Option<T> {
+        self.scope(|queue| queue.pop_front())
+    }
+}
+#[cfg(feature = "heapless")]
+impl<T, const N: usize> SharedCell<heapless::Vec<T, N>> {
+    /// Appends a value to the vector, or returns `false` if the vector is full
+    ///
+    /// # Panic
+    /// This function will panic if the cell is already locked or has been poisoned by a panicking scope
+    pub fn push(&self, value: T) -> bool {
+        self.scope(|vec| vec.push(value).is_ok())
+    }
+
+    /// Removes the last value from the vector, or returns `None` if the vector is empty
+    ///
+    /// # Panic
+    /// This function will panic if the cell is already locked or has been poisoned by a panicking scope
+    pub fn pop(&self) -> Option<T> {
+        self.scope(|vec| vec.pop())
+    }
+}
+impl<T> Default for SharedCell<T>
+where
+    T: Default,
+{
+    fn default() -> Self {
+        Self::new(T::default())
+    }
+}
+impl<T> Clone for SharedCell<T>
+where
+    T: Clone,
+{
+    fn clone(&self) -> Self {
+        self.scope(|value| Self::new(value.clone()))
+    }
+}
+impl<T> From<T> for SharedCell<T> {
+    fn from(value: T) -> Self {
+        Self::new(value)
+    }
+}
+impl<T> PartialEq for SharedCell<T>
+where
+    T: ?Sized + PartialEq,
+{
+    fn eq(&self, other: &Self) -> bool {
+        // Ensure that we don't lock the same cell twice; compare the value with itself to honor non-reflexive types
+        if ptr::eq(self, other) {
+            #[allow(clippy::eq_op)]
+            return self.scope(|value| *value == *value);
+        }
+
+        // Compare the values
+        self.scope(|value| other.scope(|other| *value == *other))
+    }
+}
+impl<T> Eq for SharedCell<T>
+where
+    T: ?Sized + Eq,
+{
+    // Marker trait, no members to implement
+}
+impl<T> Hash for SharedCell<T>
+where
+    T: ?Sized + Hash,
+{
+    fn hash<H>(&self, state: &mut H)
+    where
+        H: Hasher,
+    {
+        // Hash the value as `Some(value)`, or `None` as sentinel if the value is not accessible from the current context
+        if self.try_scope(|value| Some(&*value).hash(state)).is_err() {
+            None::<&T>.hash(state);
+        }
+    }
 }
 impl<T> Debug for SharedCell<T>
 where
-    T: Debug,
+    T: ?Sized + Debug,
 {
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
-        self.scope(|value| value.fmt(f))
+        // Return an opaque description if the cell is locked or has been poisoned
+        match self.try_scope(|value| value.fmt(f)) {
+            Ok(result) => result,
+            Err(AccessError::Reentrant) => f.debug_tuple("SharedCell").field(&"<opaque due to active lock>").finish(),
+            Err(_) => f.debug_tuple("SharedCell").field(&"<opaque due to poisoning>").finish(),
+        }
+    }
+}
+impl<T> Display for SharedCell<T>
+where
+    T: ?Sized + Display,
+{
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        // Return an opaque description if the cell is locked or has been poisoned
+        match self.try_scope(|value| value.fmt(f)) {
+            Ok(result) => result,
+            Err(AccessError::Reentrant) => f.write_str("<opaque due to active lock>"),
+            Err(_) => f.write_str("<opaque due to poisoning>"),
+        }
+    }
+}
+#[cfg(feature = "defmt")]
+impl<T> defmt::Format for SharedCell<T>
+where
+    T: ?Sized + defmt::Format,
+{
+    fn format(&self, f: defmt::Formatter) {
+        // Return an opaque description if the cell is locked or has been poisoned
+        match self.try_scope(|value| value.format(f)) {
+            Ok(_) => (),
+            Err(AccessError::Reentrant) => defmt::write!(f, "SharedCell(<opaque due to active lock>)"),
+            Err(_) => defmt::write!(f, "SharedCell(<opaque due to poisoning>)"),
+        }
+    }
+}
+#[cfg(feature = "serde")]
+impl<T> serde::Serialize for SharedCell<T>
+where
+    T: ?Sized + serde::Serialize,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        // Serialize the value or fail if it is not accessible from the current context
+        match self.try_scope(|value| value.serialize(serializer)) {
+            Ok(result) => result,
+            Err(error) => Err(serde::ser::Error::custom(error)),
+        }
+    }
+}
+#[cfg(feature = "serde")]
+impl<'de, T> serde::Deserialize<'de> for SharedCell<T>
+where
+    T: serde::Deserialize<'de>,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let value = T::deserialize(deserializer)?;
+        Ok(Self::new(value))
+    }
+}
+impl<T> Scope<T> for SharedCell<T>
+where
+    T: ?Sized,
+{
+    fn scope<F, FR>(&self, scope: F) -> FR
+    where
+        F: FnOnce(&mut T) -> FR,
+    {
+        Self::scope(self, scope)
+    }
+
+    unsafe fn as_ref_unchecked(&self) -> &T {
+        let inner_ptr = self.inner.get();
+        inner_ptr.as_ref().expect("unexpected NULL pointer inside cell")
+    }
+}
+impl<T> TryScope<T> for SharedCell<T>
+where
+    T: ?Sized,
+{
+    fn try_scope<F, FR>(&self, scope: F) -> Result<FR, AccessError>
+    where
+        F: FnOnce(&mut T) -> FR,
+    {
+        Self::try_scope(self, scope)
     }
 }
 unsafe impl<T> Sync for SharedCell<T>
 where
-    T: Send,
+    T: ?Sized + Send,
 {
     // Marker trait, no members to implement
 }
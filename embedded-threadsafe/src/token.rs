@@ -0,0 +1,43 @@
+//! A token that proves that the current context is inside a threadsafe critical section
+
+use crate::runtime;
+use core::marker::PhantomData;
+
+/// A token that proves that the current context is inside a threadsafe critical section
+///
+/// # Note
+/// The token is only ever handed out by reference for the duration of the critical section, and it is neither `Send`
+/// nor `Sync`, so it cannot escape the critical section or the current thread.
+pub struct CriticalSectionToken {
+    /// Binds the token to the current context
+    _context_bound: PhantomData<*mut ()>,
+}
+impl CriticalSectionToken {
+    /// Creates a new token
+    ///
+    /// # Safety
+    /// This function must only be called within a threadsafe critical section, and the token must not outlive it.
+    pub(crate) const unsafe fn new() -> Self {
+        Self { _context_bound: PhantomData }
+    }
+
+    /// Runs `scope` within a threadsafe critical section and passes a token that proves it
+    pub fn with<F, FR>(scope: F) -> FR
+    where
+        F: FnOnce(&Self) -> FR,
+    {
+        // Create mutable slots to transfer state to/from the closure and create the caller
+        let mut scope = Some(scope);
+        let mut result: Option<FR> = None;
+        let mut call_scope = || {
+            // Consume and call the scope
+            let scope = scope.take().expect("missing scope function");
+            let token = unsafe { Self::new() };
+            result = Some(scope(&token));
+        };
+
+        // Run the implementation in a threadsafe context and return the result
+        unsafe { runtime::_runtime_threadsafe_e0LtH0x3(&mut call_scope) };
+        result.expect("implementation scope did not set result value")
+    }
+}
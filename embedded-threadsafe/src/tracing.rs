@@ -0,0 +1,74 @@
+//! Hooks to trace cell accesses, e.g. for model checkers or static analysis tools
+
+use core::{
+    ptr,
+    sync::atomic::{AtomicPtr, Ordering},
+};
+
+/// A scope hook that is called with the address of the accessed cell and the event
+pub type ScopeHook = fn(*const (), ScopeEvent);
+
+/// A cell access event
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScopeEvent {
+    /// The cell has been locked and the scope is about to be called
+    Enter,
+    /// The scope has returned or unwound and the cell is about to be unlocked
+    Exit,
+}
+
+/// The currently registered scope hook or `NULL`
+static SCOPE_HOOK: AtomicPtr<()> = AtomicPtr::new(ptr::null_mut());
+
+/// Registers a hook that is called whenever a `SharedCell`, `InterruptCell` or `LocalCell` is accessed
+///
+/// # Note
+/// The hook is only called in debug builds, so tracing does not add any overhead to release builds. The hook is called
+/// while the cell is locked, so it must not access the traced cell itself.
+pub fn set_scope_hook(hook: ScopeHook) {
+    SCOPE_HOOK.store(hook as *mut (), Ordering::SeqCst);
+}
+
+/// Removes the registered hook if any
+pub fn clear_scope_hook() {
+    SCOPE_HOOK.store(ptr::null_mut(), Ordering::SeqCst);
+}
+
+/// Calls the registered hook if any
+#[cfg(debug_assertions)]
+fn fire(cell: *const (), event: ScopeEvent) {
+    // Get the hook if any
+    let hook = SCOPE_HOOK.load(Ordering::SeqCst);
+    if hook.is_null() {
+        return;
+    }
+
+    // Call the hook; the pointer has been created from a `ScopeHook` in `set_scope_hook`
+    let hook: ScopeHook = unsafe { core::mem::transmute::<*mut (), ScopeHook>(hook) };
+    hook(cell, event)
+}
+
+/// Fires [`ScopeEvent::Enter`] on creation and [`ScopeEvent::Exit`] on drop
+#[cfg(debug_assertions)]
+pub(crate) struct ScopeTrace {
+    /// The address of the accessed cell
+    cell: *const (),
+}
+#[cfg(debug_assertions)]
+impl ScopeTrace {
+    /// Fires [`ScopeEvent::Enter`] for the given cell
+    pub fn enter<T>(cell: &T) -> Self
+    where
+        T: ?Sized,
+    {
+        let cell = (cell as *const T).cast();
+        fire(cell, ScopeEvent::Enter);
+        Self { cell }
+    }
+}
+#[cfg(debug_assertions)]
+impl Drop for ScopeTrace {
+    fn drop(&mut self) {
+        fire(self.cell, ScopeEvent::Exit);
+    }
+}
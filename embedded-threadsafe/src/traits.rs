@@ -0,0 +1,146 @@
+//! Traits to abstract over the different cell types
+
+use crate::error::AccessError;
+use core::{mem, pin::Pin};
+
+/// A cell that provides scoped access to its underlying value
+pub trait Scope<T>
+where
+    T: ?Sized,
+{
+    /// Provides scoped access to the underlying value
+    ///
+    /// # Panic
+    /// This function may panic if the underlying value cannot be accessed from the current context
+    fn scope<F, FR>(&self, scope: F) -> FR
+    where
+        F: FnOnce(&mut T) -> FR;
+
+    /// Gets an unchecked shared reference to the underlying value
+    ///
+    /// # Safety
+    /// This function provides unchecked access to the underlying value; the caller must ensure that the value is not
+    /// modified or mutably borrowed (e.g. via a scope in another context) for as long as the reference is alive.
+    unsafe fn as_ref_unchecked(&self) -> &T;
+
+    /// Provides scoped access to the underlying value if `condition` is `true`, or returns `None` without accessing the
+    /// cell otherwise
+    ///
+    /// # Panic
+    /// This function may panic if `condition` is `true` and the underlying value cannot be accessed from the current
+    /// context
+    fn scope_if<F, FR>(&self, condition: bool, scope: F) -> Option<FR>
+    where
+        F: FnOnce(&mut T) -> FR,
+    {
+        // Skip the access entirely if the condition is not met
+        if !condition {
+            return None;
+        }
+        Some(self.scope(scope))
+    }
+
+    /// Provides scoped read-only access to the underlying value
+    ///
+    /// # Panic
+    /// This function may panic if the underlying value cannot be accessed from the current context
+    fn with_ref<F, FR>(&self, scope: F) -> FR
+    where
+        F: FnOnce(&T) -> FR,
+    {
+        self.scope(|value| scope(value))
+    }
+
+    /// Gets a copy of the underlying value
+    ///
+    /// # Panic
+    /// This function may panic if the underlying value cannot be accessed from the current context
+    fn get(&self) -> T
+    where
+        T: Copy,
+    {
+        self.scope(|value| *value)
+    }
+
+    /// Replaces the underlying value with `new_value`
+    ///
+    /// # Panic
+    /// This function may panic if the underlying value cannot be accessed from the current context
+    fn set(&self, new_value: T)
+    where
+        T: Sized,
+    {
+        self.scope(|value| *value = new_value)
+    }
+
+    /// Gets a point-in-time clone of the underlying value, so it can be processed outside of the scope
+    ///
+    /// # Panic
+    /// This function may panic if the underlying value cannot be accessed from the current context
+    fn snapshot(&self) -> T
+    where
+        T: Clone,
+    {
+        self.scope(|value| value.clone())
+    }
+
+    /// Provides scoped access to the underlying value as pinned reference
+    ///
+    /// # Panic
+    /// This function may panic if the underlying value cannot be accessed from the current context
+    ///
+    /// # Safety
+    /// The caller must ensure that the value is never moved out of the cell once it has been pinned, i.e. it must not
+    /// be replaced or swapped via any other scope for as long as the cell exists.
+    unsafe fn scope_pin<F, FR>(&self, scope: F) -> FR
+    where
+        F: FnOnce(Pin<&mut T>) -> FR,
+    {
+        self.scope(|value| scope(Pin::new_unchecked(value)))
+    }
+
+    /// Replaces the underlying value with the result of `map`, which consumes the current value
+    ///
+    /// # Note
+    /// The value is temporarily replaced with `T::default()` while `map` is running, so it is left in the default
+    /// state if `map` panics.
+    ///
+    /// # Panic
+    /// This function may panic if the underlying value cannot be accessed from the current context
+    fn scope_map<F>(&self, map: F)
+    where
+        T: Default,
+        F: FnOnce(T) -> T,
+    {
+        self.scope(|value| *value = map(mem::take(value)))
+    }
+}
+
+/// A cell that provides fallible scoped access to its underlying value
+pub trait TryScope<T>
+where
+    T: ?Sized,
+{
+    /// Provides scoped access to the underlying value if it can be accessed from the current context
+    fn try_scope<F, FR>(&self, scope: F) -> Result<FR, AccessError>
+    where
+        F: FnOnce(&mut T) -> FR;
+
+    /// Provides scoped access to the underlying value if it can be accessed from the current context, or returns
+    /// `default` otherwise
+    fn scope_or<F, FR>(&self, default: FR, scope: F) -> FR
+    where
+        F: FnOnce(&mut T) -> FR,
+    {
+        self.try_scope(scope).unwrap_or(default)
+    }
+
+    /// Returns a clone of the underlying value if it can be accessed from the current context, or `T::default()`
+    /// otherwise
+    fn scope_or_default(&self) -> T
+    where
+        T: Default + Clone,
+    {
+        self.try_scope(|value| value.clone()).unwrap_or_default()
+    }
+}
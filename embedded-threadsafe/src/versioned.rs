@@ -0,0 +1,64 @@
+//! A shared cell with a generation counter to detect stale reads
+
+use crate::{error::VersionMismatch, SharedCell};
+
+/// A cell that can be safely be shared across thread boundaries and interrupt contexts, and that counts write accesses
+pub struct VersionedCell<T> {
+    /// The generation counter and the wrapped value
+    inner: SharedCell<(u64, T)>,
+}
+impl<T> VersionedCell<T> {
+    /// Creates a new cell with the generation `0`
+    pub const fn new(value: T) -> Self {
+        Self { inner: SharedCell::new((0, value)) }
+    }
+
+    /// Provides scoped mutable access to the underlying value, increments the generation and returns the new
+    /// generation alongside the scope result
+    pub fn scope_versioned<F, FR>(&self, scope: F) -> (u64, FR)
+    where
+        F: FnOnce(&mut T) -> FR,
+    {
+        self.inner.scope(|(generation, value)| {
+            *generation += 1;
+            let result = scope(value);
+            (*generation, result)
+        })
+    }
+
+    /// Provides scoped mutable access to the underlying value if the generation still equals `expected`, increments the
+    /// generation and returns the new generation alongside the scope result
+    ///
+    /// # Note
+    /// This allows to detect intervening writes, e.g. if an async task reads the generation via [`Self::version`],
+    /// suspends, and wants to modify the value afterwards. If the generation does not match, `scope` is not called.
+    pub fn scope_with_version<F, FR>(&self, expected: u64, scope: F) -> Result<(u64, FR), VersionMismatch>
+    where
+        F: FnOnce(&mut T) -> FR,
+    {
+        self.inner.scope(|(generation, value)| {
+            // Ensure that the value has not been modified in the meantime
+            if *generation != expected {
+                return Err(VersionMismatch { expected, actual: *generation });
+            }
+
+            *generation += 1;
+            let result = scope(value);
+            Ok((*generation, result))
+        })
+    }
+
+    /// Provides scoped read-only access to the underlying value and returns the current generation alongside the scope
+    /// result
+    pub fn read_versioned<F, FR>(&self, scope: F) -> (u64, FR)
+    where
+        F: FnOnce(&T) -> FR,
+    {
+        self.inner.scope(|(generation, value)| (*generation, scope(value)))
+    }
+
+    /// Gets the current generation
+    pub fn version(&self) -> u64 {
+        self.inner.scope(|(generation, _)| *generation)
+    }
+}
@@ -0,0 +1,59 @@
+//! A reference that only allows volatile reads and writes to the underlying value
+
+use core::{
+    fmt::{self, Debug, Formatter},
+    marker::PhantomData,
+    ptr,
+};
+
+/// A reference that only allows volatile reads and writes to the underlying value
+///
+/// # Note
+/// Volatile accesses are never elided or reordered with other volatile accesses by the compiler, which is necessary if
+/// the value is also accessed by hardware (e.g. a DMA controller).
+pub struct VolatileRef<'a, T> {
+    /// The referenced value
+    value: *mut T,
+    /// Binds the reference to the lifetime of the mutable borrow
+    _lifetime: PhantomData<&'a mut T>,
+}
+impl<'a, T> VolatileRef<'a, T> {
+    /// Creates a new volatile reference
+    pub fn new(value: &'a mut T) -> Self {
+        Self { value, _lifetime: PhantomData }
+    }
+
+    /// Performs a volatile read of the underlying value
+    pub fn read(&self) -> T
+    where
+        T: Copy,
+    {
+        unsafe { ptr::read_volatile(self.value) }
+    }
+
+    /// Performs a volatile write of `value` to the underlying value
+    pub fn write(&mut self, value: T)
+    where
+        T: Copy,
+    {
+        unsafe { ptr::write_volatile(self.value, value) }
+    }
+
+    /// Performs a volatile read of the underlying value, and a volatile write of the result of `update`
+    pub fn update<F>(&mut self, update: F)
+    where
+        T: Copy,
+        F: FnOnce(T) -> T,
+    {
+        let value = self.read();
+        self.write(update(value));
+    }
+}
+impl<T> Debug for VolatileRef<'_, T>
+where
+    T: Copy + Debug,
+{
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_tuple("VolatileRef").field(&self.read()).finish()
+    }
+}
@@ -0,0 +1,52 @@
+//! A last-writer-wins shared cell with change notification
+
+use crate::VersionedCell;
+use core::cell::Cell;
+
+/// A cell that can be safely be shared across thread boundaries and interrupt contexts, where the latest sent value
+/// wins and receivers can detect changes
+pub struct WatchCell<T> {
+    /// The versioned value
+    inner: VersionedCell<T>,
+}
+impl<T> WatchCell<T> {
+    /// Creates a new watch cell with the given initial value
+    pub const fn new(value: T) -> Self {
+        Self { inner: VersionedCell::new(value) }
+    }
+
+    /// Replaces the current value
+    pub fn send(&self, value: T) {
+        self.inner.scope_versioned(|current| *current = value);
+    }
+
+    /// Creates a new receiver which considers the current value as seen
+    pub fn receiver(&self) -> WatchReceiver<'_, T> {
+        let seen = self.inner.version();
+        WatchReceiver { cell: self, seen: Cell::new(seen) }
+    }
+}
+
+/// A receiver that observes a [`WatchCell`]
+pub struct WatchReceiver<'a, T> {
+    /// The observed cell
+    cell: &'a WatchCell<T>,
+    /// The generation of the last seen value
+    seen: Cell<u64>,
+}
+impl<T> WatchReceiver<'_, T> {
+    /// Tests whether a new value has been sent since the last value was seen by this receiver
+    pub fn changed(&self) -> bool {
+        self.cell.inner.version() != self.seen.get()
+    }
+
+    /// Returns a clone of the latest value and marks it as seen
+    pub fn borrow(&self) -> T
+    where
+        T: Clone,
+    {
+        let (generation, value) = self.cell.inner.read_versioned(T::clone);
+        self.seen.set(generation);
+        value
+    }
+}